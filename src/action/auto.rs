@@ -0,0 +1,134 @@
+//! Non-interactive picking of replacements, suitable for scripts and bots.
+//!
+//! Every suggestion of an allowed detector is turned into a bandaid
+//! using its first replacement, which is the one ranked highest by the checker.
+
+use super::*;
+
+use enumflags2::BitFlags;
+use std::convert::TryFrom;
+
+impl UserPicked {
+    /// Pick the first replacement of every suggestion found by one of the `allowed` detectors.
+    ///
+    /// Suggestions without replacements, spanning multiple lines or overlapping
+    /// with an already picked one are skipped.
+    pub(super) fn select_auto<'s>(
+        suggestions_per_path: SuggestionSet<'s>,
+        allowed: BitFlags<Detector>,
+        _config: &Config,
+    ) -> Self {
+        let mut picked = UserPicked::default();
+
+        for (origin, mut suggestions) in suggestions_per_path {
+            suggestions.sort_by_key(|suggestion| {
+                (suggestion.span.start.line, suggestion.span.start.column)
+            });
+
+            let mut previous: Option<Span> = None;
+            for suggestion in suggestions {
+                if !allowed.contains(suggestion.detector) {
+                    trace!("Detector {} is not allowed, skip", suggestion.detector);
+                    continue;
+                }
+                if suggestion.span.start.line != suggestion.span.end.line {
+                    debug!(
+                        "Suggestion spans multiple lines, skip: {:?}",
+                        suggestion.span
+                    );
+                    continue;
+                }
+                if let Some(previous) = previous {
+                    if previous.end.line == suggestion.span.start.line
+                        && previous.end.column >= suggestion.span.start.column
+                    {
+                        debug!("Suggestion overlaps with a previous one, skip");
+                        continue;
+                    }
+                }
+                match BandAid::try_from((&suggestion, 0)) {
+                    Ok(bandaid) => {
+                        previous = Some(suggestion.span);
+                        picked.add_bandaid(&origin, bandaid);
+                    }
+                    Err(_) => trace!("Suggestion did not contain a replacement, skip"),
+                }
+            }
+        }
+        picked
+    }
+
+    /// Print the picked replacements as `path:line:column: replacement` instead of applying them.
+    pub(super) fn print_dry_run<W: Write>(&self, mut sink: W) -> Result<()> {
+        for (origin, bandaids) in self.bandaids.iter() {
+            let path = crate::output::display_path(origin.as_path());
+            for bandaid in bandaids {
+                writeln!(
+                    sink,
+                    "{}:{}:{}: replace with `{}`",
+                    path,
+                    bandaid.span.start.line,
+                    bandaid.span.start.column + 1,
+                    bandaid.replacement
+                )?;
+            }
+        }
+        sink.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::tests::{chunk, suggestion};
+
+    #[test]
+    fn auto_pick() {
+        let chunk = chunk();
+        let mut set = SuggestionSet::new();
+        // picked, first replacement wins
+        set.add(ContentOrigin::TestEntity, suggestion(&chunk));
+        // overlaps with the first one
+        let mut overlapping = suggestion(&chunk);
+        overlapping.span.start.column = 8;
+        overlapping.span.end.column = 12;
+        set.add(ContentOrigin::TestEntity, overlapping);
+        // no replacements
+        let mut empty = suggestion(&chunk);
+        empty.span.start.line = 2;
+        empty.span.end.line = 2;
+        empty.replacements.clear();
+        set.add(ContentOrigin::TestEntity, empty);
+        // multiple lines
+        let mut multi = suggestion(&chunk);
+        multi.span.start.line = 3;
+        multi.span.end.line = 4;
+        set.add(ContentOrigin::TestEntity, multi);
+
+        let picked = UserPicked::select_auto(set, Detector::Dummy.into(), &Config::default());
+        assert_eq!(picked.total_count(), 1);
+        let bandaid = &picked.bandaids[&ContentOrigin::TestEntity][0];
+        assert_eq!(bandaid.replacement, "dyck");
+        assert_eq!(bandaid.span.start.column, 6);
+
+        let mut sink = Vec::<u8>::with_capacity(128);
+        picked
+            .print_dry_run(&mut sink)
+            .expect("Writing to a vec never fails");
+        assert_eq!(
+            String::from_utf8(sink).unwrap(),
+            "/tmp/test/entity:1:7: replace with `dyck`\n"
+        );
+    }
+
+    #[test]
+    fn auto_pick_disallowed() {
+        let chunk = chunk();
+        let mut set = SuggestionSet::new();
+        set.add(ContentOrigin::TestEntity, suggestion(&chunk));
+
+        let picked = UserPicked::select_auto(set, Detector::Hunspell.into(), &Config::default());
+        assert_eq!(picked.total_count(), 0);
+    }
+}
@@ -0,0 +1,266 @@
+//! Baseline of accepted findings.
+//!
+//! Snapshots all findings of a workspace, so only newly introduced
+//! mistakes are reported by `check --baseline=<file>`.
+//!
+//! Entries are keyed by the path, the detector, the flagged word and the
+//! trimmed line of the checked chunk it was found in, rather than by line
+//! numbers, so unrelated edits above a finding do not invalidate the baseline.
+//! Paths are relative to the project root, see `traverse::project_root`, so
+//! the baseline does not depend on the directory it is recorded or checked from.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "entries": [
+//!     {
+//!       "path": "src/main.rs",
+//!       "detector": "Hunspell",
+//!       "word": "dyrck",
+//!       "context": "Is it dyrck again?",
+//!       "count": 1
+//!     }
+//!   ]
+//! }
+//! ```
+
+use crate::output::relative_to;
+use crate::util::sub_chars;
+use crate::{Range, Suggestion, SuggestionSet};
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Version of the baseline file format.
+pub const BASELINE_VERSION: u32 = 1;
+
+/// Identifies a finding independently of its exact location.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Key {
+    path: String,
+    detector: String,
+    word: String,
+    context: String,
+}
+
+impl Key {
+    /// The key of `suggestion`, with its path relative to the project `root`.
+    fn new(suggestion: &Suggestion<'_>, root: &Path) -> Self {
+        let chunk = suggestion.chunk.as_str();
+        let path = suggestion.origin.as_path();
+        Self {
+            path: relative_to(root, path).unwrap_or_else(|| path.display().to_string()),
+            detector: suggestion.detector.to_string(),
+            word: sub_chars(chunk, suggestion.range.clone()),
+            context: context(chunk, suggestion.range.clone()),
+        }
+    }
+}
+
+/// Extract the trimmed line of `chunk` the char `range` starts in.
+pub(crate) fn context(chunk: &str, range: Range) -> String {
+    let mut offset = 0usize;
+    for line in chunk.split('\n') {
+        let len = line.chars().count();
+        if range.start <= offset + len {
+            return line.trim().to_owned();
+        }
+        offset += len + 1;
+    }
+    String::new()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub path: String,
+    pub detector: String,
+    pub word: String,
+    pub context: String,
+    /// Number of identical findings, i.e. the same typo twice on one line.
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+}
+
+/// A set of accepted findings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    entries: BTreeMap<Key, usize>,
+}
+
+impl Baseline {
+    /// Snapshot all findings of the suggestion set, found in the project at `root`.
+    pub fn from_suggestions(set: &SuggestionSet, root: &Path) -> Self {
+        let mut entries = BTreeMap::new();
+        for (_origin, suggestions) in set.iter() {
+            for suggestion in suggestions {
+                *entries.entry(Key::new(suggestion, root)).or_insert(0) += 1;
+            }
+        }
+        Self { entries }
+    }
+
+    /// Number of findings covered by the baseline.
+    pub fn total_count(&self) -> usize {
+        self.entries.values().sum()
+    }
+
+    /// Serialize the baseline, entries are sorted to keep diffs minimal.
+    pub fn to_json(&self) -> Result<String> {
+        let file = BaselineFile {
+            version: BASELINE_VERSION,
+            entries: self
+                .entries
+                .iter()
+                .map(|(key, &count)| BaselineEntry {
+                    path: key.path.clone(),
+                    detector: key.detector.clone(),
+                    word: key.word.clone(),
+                    context: key.context.clone(),
+                    count,
+                })
+                .collect(),
+        };
+        let mut json = serde_json::to_string_pretty(&file)?;
+        json.push('\n');
+        Ok(json)
+    }
+
+    /// Parse a serialized baseline.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let file: BaselineFile = serde_json::from_str(json)?;
+        if file.version != BASELINE_VERSION {
+            bail!(
+                "Unsupported baseline version {}, expected {}",
+                file.version,
+                BASELINE_VERSION
+            );
+        }
+        let mut entries = BTreeMap::new();
+        for entry in file.entries {
+            let key = Key {
+                path: entry.path,
+                detector: entry.detector,
+                word: entry.word,
+                context: entry.context,
+            };
+            *entries.entry(key).or_insert(0) += entry.count;
+        }
+        Ok(Self { entries })
+    }
+
+    /// Load a baseline from disk.
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read baseline {}", path.display()).context(e))?;
+        Self::from_json(&json)
+            .map_err(|e| anyhow!("Failed to parse baseline {}", path.display()).context(e))
+    }
+
+    /// Write the baseline to disk, overwriting any previous one.
+    pub fn store(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json()?)
+            .map_err(|e| anyhow!("Failed to write baseline {}", path.display()).context(e))
+    }
+
+    /// Retain only the findings which are not covered by the baseline.
+    ///
+    /// Each entry covers up to `count` identical findings, any additional
+    /// occurrence is considered new.
+    pub fn filter<'s>(&self, set: SuggestionSet<'s>, root: &Path) -> SuggestionSet<'s> {
+        self.clone().consume(set, root)
+    }
+
+    /// Same as `filter`, but the accepted findings are used up, so the baseline
+    /// can be applied to parts of the findings one after another.
+    pub fn consume<'s>(&mut self, set: SuggestionSet<'s>, root: &Path) -> SuggestionSet<'s> {
+        let remaining = &mut self.entries;
+        let mut filtered = SuggestionSet::new();
+        for (origin, suggestions) in set {
+            let fresh = suggestions
+                .into_iter()
+                .filter(
+                    |suggestion| match remaining.get_mut(&Key::new(suggestion, root)) {
+                        Some(count) if *count > 0 => {
+                            *count -= 1;
+                            false
+                        }
+                        _ => true,
+                    },
+                )
+                .collect::<Vec<_>>();
+            if !fresh.is_empty() {
+                filtered.extend(origin, fresh);
+            }
+        }
+        filtered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::tests::{chunk, suggestion};
+
+    #[test]
+    fn context_line() {
+        assert_eq!(
+            context(" first\n  second line \nthird", 10..13),
+            "second line"
+        );
+        assert_eq!(context(" Is it dyrck again?", 7..12), "Is it dyrck again?");
+    }
+
+    #[test]
+    fn roundtrip() {
+        let chunk = chunk();
+        let mut set = SuggestionSet::new();
+        set.add(suggestion(&chunk).origin.clone(), suggestion(&chunk));
+
+        let baseline = Baseline::from_suggestions(&set, Path::new("/tmp"));
+        assert_eq!(baseline.total_count(), 1);
+        let json = baseline.to_json().unwrap();
+        assert!(json.contains(r#""context": "Is it dyrck again?""#));
+        assert!(json.contains(r#""path": "test/entity""#));
+        assert_eq!(Baseline::from_json(&json).unwrap(), baseline);
+
+        assert!(Baseline::from_json(r#"{ "version": 0, "entries": [] }"#).is_err());
+    }
+
+    #[test]
+    fn only_new_findings() {
+        let chunk = chunk();
+        let mut set = SuggestionSet::new();
+        set.add(suggestion(&chunk).origin.clone(), suggestion(&chunk));
+        let baseline = Baseline::from_suggestions(&set, Path::new("/tmp"));
+
+        // moving the finding to another line keeps it covered
+        let mut moved = suggestion(&chunk);
+        moved.span.start.line += 10;
+        moved.span.end.line += 10;
+        let mut set = SuggestionSet::new();
+        set.add(moved.origin.clone(), moved);
+        assert_eq!(baseline.filter(set, Path::new("/tmp")).total_count(), 0);
+
+        // a second occurrence is new
+        let mut set = SuggestionSet::new();
+        set.add(suggestion(&chunk).origin.clone(), suggestion(&chunk));
+        set.add(suggestion(&chunk).origin.clone(), suggestion(&chunk));
+        assert_eq!(baseline.filter(set, Path::new("/tmp")).total_count(), 1);
+
+        // the same file relative to another root is a different finding
+        let mut set = SuggestionSet::new();
+        set.add(suggestion(&chunk).origin.clone(), suggestion(&chunk));
+        assert_eq!(
+            baseline.filter(set, Path::new("/tmp/test")).total_count(),
+            1
+        );
+    }
+}
@@ -19,6 +19,7 @@ use std::io::stdout;
 const HELP: &'static str = r##"y - apply this suggestion
 n - do not apply the suggested correction
 q - quit; do not stage this hunk or any of the remaining ones
+a - add the word to the project word list and skip it from now on
 d - do not apply this suggestion and skip the rest of the file
 g - select a suggestion to go to
 j - leave this hunk undecided, see next undecided hunk
@@ -72,6 +73,8 @@ pub(super) enum UserSelection {
     Help,
     /// Skip the remaining fixes for the current file.
     SkipFile,
+    /// The word is correct, add it to the project word list.
+    AddToDictionary(String),
     /// Stop execution.
     Abort,
     /// continue as if whatever returned this was never called.
@@ -152,8 +155,26 @@ impl UserPicked {
         self.bandaids.iter().map(|(_origin, vec)| vec.len()).sum()
     }
 
+    /// The bandaids of every file, ordered by their position as `correct_lines` requires.
+    ///
+    /// Dev comments are checked after the doc comments, so they are picked out of order,
+    /// and the doc tests of a file have origins of their own.
+    pub(super) fn into_files(self) -> indexmap::IndexMap<PathBuf, Vec<BandAid>> {
+        let mut files = indexmap::IndexMap::<PathBuf, Vec<BandAid>>::new();
+        for (origin, bandaids) in self.bandaids {
+            files
+                .entry(origin.as_path().to_owned())
+                .or_default()
+                .extend(bandaids);
+        }
+        for bandaids in files.values_mut() {
+            bandaids.sort_by_key(|bandaid| (bandaid.span.start.line, bandaid.span.start.column));
+        }
+        files
+    }
+
     /// Apply a single bandaid.
-    fn add_bandaid<'u>(&mut self, origin: &ContentOrigin, fix: BandAid) {
+    pub(super) fn add_bandaid<'u>(&mut self, origin: &ContentOrigin, fix: BandAid) {
         self.bandaids
             .entry(origin.clone())
             .or_insert_with(|| Vec::with_capacity(10))
@@ -457,6 +478,13 @@ impl UserPicked {
                     return Ok(UserSelection::Abort)
                 }
                 KeyCode::Char('d') => return Ok(UserSelection::SkipFile),
+                KeyCode::Char('a') => {
+                    let word = crate::util::sub_chars(
+                        state.suggestion.chunk.as_str(),
+                        state.suggestion.range.clone(),
+                    );
+                    return Ok(UserSelection::AddToDictionary(word));
+                }
                 KeyCode::Char('e') => {
                     // jump to the user input entry
                     state.select_custom();
@@ -472,10 +500,14 @@ impl UserPicked {
 
     pub(super) fn select_interactive<'s>(
         suggestions_per_path: SuggestionSet<'s>,
-        _config: &Config,
+        config: &Config,
     ) -> Result<(Self, UserSelection)> {
         let mut picked = UserPicked::default();
 
+        // words added to the project word list during this session
+        let mut learned = std::collections::HashSet::<String>::new();
+        let project_wordlist = config.project_wordlist();
+
         trace!("Select the ones to actully use");
 
         for (origin, suggestions) in suggestions_per_path {
@@ -512,6 +544,13 @@ impl UserPicked {
                     trace!("Suggestion did not contain a replacement, skip");
                     continue;
                 }
+                if learned.contains(&crate::util::sub_chars(
+                    suggestion.chunk.as_str(),
+                    suggestion.range.clone(),
+                )) {
+                    trace!("Word was added to the project word list, skip");
+                    continue;
+                }
                 println!("{}", suggestion);
 
                 let mut state = State::from(&suggestion);
@@ -533,6 +572,17 @@ impl UserPicked {
                     UserSelection::Replacement(bandaid) => {
                         picked.add_bandaid(&origin, bandaid);
                     }
+                    UserSelection::AddToDictionary(word) => {
+                        if let Some(project_wordlist) = project_wordlist {
+                            crate::checker::wordlist::append(project_wordlist, &word)?;
+                        } else {
+                            warn!(
+                                "No `project_wordlist` configured, `{}` is only skipped for this run",
+                                word
+                            );
+                        }
+                        learned.insert(word);
+                    }
                     _ => continue,
                 };
 
@@ -1,17 +1,23 @@
 use super::*;
 use anyhow::{anyhow, Result};
-use log::{debug, trace};
-use std::convert::TryInto;
+use enumflags2::BitFlags;
+use log::{debug, info, trace, warn};
 use std::fs::{self, OpenOptions};
 use std::io::{BufRead, Read, Write};
 
 use std::path::PathBuf;
 
+pub mod auto;
 pub mod bandaid;
+pub mod baseline;
 pub mod interactive;
+pub mod patch;
+pub mod reflow;
 
 pub(crate) use bandaid::*;
+pub use baseline::Baseline;
 use interactive::*;
+pub use patch::Destination;
 
 #[derive(Debug, Clone, Copy)]
 pub enum Finish {
@@ -31,6 +37,9 @@ impl Finish {
 /// correct all lines
 /// `bandaids` are the fixes to be applied to the lines
 ///
+/// A bandaid may span multiple lines, in which case the covered lines
+/// are replaced as a whole by its replacement, which can itself contain newlines.
+///
 /// Note that `Lines` as created by `(x as BufLines).lines()` does
 /// not preserve trailing newlines, so either the iterator
 /// needs to be modified to yield an extra (i.e. with `.chain("".to_owned())`)
@@ -41,105 +50,89 @@ fn correct_lines<'s>(
     mut sink: impl Write,
 ) -> Result<()> {
     let mut nxt: Option<BandAid> = bandaids.next();
+    // line and column at which the content continues after a multiline bandaid
+    let mut resume: Option<(usize, usize)> = None;
     for (line_number, content) in source {
         trace!("Processing line {}", line_number);
         let mut remainder_column = 0usize;
-        // let content: String = content.map_err(|e| {
-        //     anyhow!("Line {} contains invalid utf8 characters", line_number).context(e)
-        // })?;
-
-        if nxt.is_none() {
-            // no candidates remaining, just keep going
-            sink.write(content.as_bytes())?;
-            sink.write("\n".as_bytes())?;
-            continue;
-        }
 
-        if let Some(ref bandaid) = nxt {
-            if !bandaid.span.covers_line(line_number) {
-                sink.write(content.as_bytes())?;
-                sink.write("\n".as_bytes())?;
+        if let Some((line, column)) = resume {
+            if line_number < line {
+                // entirely covered by the replacement
                 continue;
             }
+            resume = None;
+            remainder_column = column;
         }
 
         let content_len = content.chars().count();
         while let Some(bandaid) = nxt.take() {
+            if bandaid.span.start.line > line_number {
+                nxt = Some(bandaid);
+                break;
+            }
+            nxt = bandaids.next();
+            if bandaid.span.start.line < line_number {
+                warn!("Skipping bandaid {:?} overlapping a previous one", bandaid);
+                continue;
+            }
             trace!("Applying next bandaid {:?}", bandaid);
             trace!("where line {} is: >{}<", line_number, content);
-            let range: Range = bandaid
-                .span
-                .try_into()
-                .expect("There should be no multiline strings as of today");
+            let start = bandaid.span.start.column;
             // write prelude for this line between start or previous replacement
-            if range.start > remainder_column {
-                sink.write(util::sub_chars(&content, remainder_column..range.start).as_bytes())?;
+            if start > remainder_column {
+                sink.write(util::sub_chars(&content, remainder_column..start).as_bytes())?;
             }
             // write the replacement chunk
             sink.write(bandaid.replacement.as_bytes())?;
 
-            remainder_column = range.end;
-            nxt = bandaids.next();
-            let complete_current_line = if let Some(ref bandaid) = nxt {
-                // if `nxt` is also targeting the current line, don't complete the line
-                !bandaid.span.covers_line(line_number)
-            } else {
-                true
-            };
-            if complete_current_line {
-                // the last replacement may be the end of content
-                if remainder_column < content_len {
-                    debug!(
-                        "line {} len is {}, and remainder column is {}",
-                        line_number, content_len, remainder_column
-                    );
-                    // otherwise write all
-                    // not that this also covers writing a line without any suggestions
-                    sink.write(
-                        util::sub_chars(&content, remainder_column..content_len).as_bytes(),
-                    )?;
-                } else {
-                    debug!(
-                        "line {} len is {}, and remainder column is {}",
-                        line_number, content_len, remainder_column
-                    );
-                }
-                sink.write("\n".as_bytes())?;
-                // break the inner loop
+            remainder_column = bandaid.span.end.column + 1;
+            if bandaid.span.end.line > line_number {
+                resume = Some((bandaid.span.end.line, remainder_column));
                 break;
-                // } else {
-                // next suggestion covers same line
             }
         }
+
+        if resume.is_some() {
+            // the line is completed by the one the bandaid ends in
+            continue;
+        }
+        // the last replacement may be the end of content
+        if remainder_column < content_len {
+            // not that this also covers writing a line without any suggestions
+            sink.write(util::sub_chars(&content, remainder_column..content_len).as_bytes())?;
+        } else {
+            debug!(
+                "line {} len is {}, and remainder column is {}",
+                line_number, content_len, remainder_column
+            );
+        }
+        sink.write("\n".as_bytes())?;
     }
     Ok(())
 }
 
 /// Mode in which `cargo-spellcheck` operates
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Action {
-    /// Only show errors
-    Check,
+    /// Only show errors, printed in the given format.
+    Check(OutputFormat),
     /// Interactively choose from checker provided suggestions.
-    Fix,
+    Fix(Destination),
+    /// Apply the first replacement of all suggestions by one of the given detectors,
+    /// without asking. With `dry_run` the replacements are only printed.
+    AutoFix {
+        dry_run: bool,
+        detectors: BitFlags<Detector>,
+        destination: Destination,
+    },
+    /// Record all findings as accepted baseline in the given file.
+    Baseline(PathBuf),
+    /// Re-wrap the paragraphs of doc comments to the configured line length.
+    Reflow(Destination),
 }
 
 impl Action {
-    fn correction<'s>(
-        &self,
-        origin: ContentOrigin,
-        bandaids: impl IntoIterator<Item = BandAid>,
-    ) -> Result<()> {
-        match origin {
-            ContentOrigin::CommonMarkFile(path) => self.correct_file(path, bandaids),
-            ContentOrigin::RustSourceFile(path) => self.correct_file(path, bandaids),
-            //@todo bandaids are relative to the doc-test, so fix the span with the one provided
-            ContentOrigin::RustDocTest(path, _span) => self.correct_file(path, bandaids),
-            #[cfg(test)]
-            ContentOrigin::TestEntity => unreachable!("Use a proper file"),
-        }
-    }
-
     /// assumes suggestions are sorted by line number and column number and must be non overlapping
     fn correct_file<'s>(
         &self,
@@ -196,8 +189,8 @@ impl Action {
     pub fn write_changes_to_disk(&self, userpicked: UserPicked, _config: &Config) -> Result<()> {
         if userpicked.total_count() > 0 {
             debug!("Writing changes back to disk");
-            for (path, bandaids) in userpicked.bandaids.into_iter() {
-                self.correction(path, bandaids.into_iter())?;
+            for (path, bandaids) in userpicked.into_files() {
+                self.correct_file(path, bandaids)?;
             }
         } else {
             debug!("No band aids to apply");
@@ -206,32 +199,67 @@ impl Action {
     }
 
     /// Purpose was to check, check complete, so print the results.
-    fn check(&self, suggestions_per_path: SuggestionSet, _config: &Config) -> Result<Finish> {
-        let mut count = 0usize;
-        for (_path, suggestions) in suggestions_per_path {
-            count += suggestions.len();
-            for suggestion in suggestions {
-                println!("{}", suggestion);
-            }
-        }
+    fn check(
+        &self,
+        suggestions_per_path: SuggestionSet,
+        format: OutputFormat,
+        _config: &Config,
+    ) -> Result<Finish> {
+        let count = suggestions_per_path.total_count();
+        let stdout = std::io::stdout();
+        format.emit(&suggestions_per_path, stdout.lock())?;
         Ok(Finish::MistakeCount(count))
     }
 
     /// Run the requested action.
-    pub fn run(self, suggestions: SuggestionSet, config: &Config) -> Result<Finish> {
+    ///
+    /// All actions but `Reflow` only act upon the `suggestions`.
+    pub fn run(
+        self,
+        documentation: &Documentation,
+        suggestions: SuggestionSet,
+        config: &Config,
+    ) -> Result<Finish> {
         match self {
-            Self::Check => self.check(suggestions, config),
-            Self::Fix => {
+            Self::Check(format) => self.check(suggestions, format, config),
+            Self::Fix(ref destination) => {
                 let (picked, user_sel) =
                     interactive::UserPicked::select_interactive(suggestions, config)?;
                 if user_sel == UserSelection::Abort {
                     Ok(Finish::Abort)
                 } else {
                     let n = picked.total_count();
-                    self.write_changes_to_disk(picked, config)?;
+                    self.apply(picked, destination, config)?;
                     Ok(Finish::MistakeCount(n))
                 }
             }
+            Self::AutoFix {
+                dry_run,
+                detectors,
+                ref destination,
+            } => {
+                let picked = UserPicked::select_auto(suggestions, detectors, config);
+                let n = picked.total_count();
+                if dry_run {
+                    let stdout = std::io::stdout();
+                    picked.print_dry_run(stdout.lock())?;
+                } else {
+                    self.apply(picked, destination, config)?;
+                }
+                Ok(Finish::MistakeCount(n))
+            }
+            Self::Baseline(ref path) => {
+                let root = traverse::project_root(&traverse::cwd()?);
+                let baseline = Baseline::from_suggestions(&suggestions, &root);
+                baseline.store(path)?;
+                info!(
+                    "Recorded {} findings in baseline {}",
+                    baseline.total_count(),
+                    path.display()
+                );
+                Ok(Finish::MistakeCount(0))
+            }
+            Self::Reflow(ref destination) => self.reflow(documentation, destination, config),
         }
     }
 }
@@ -239,6 +267,7 @@ impl Action {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::convert::TryInto;
 
     const TEXT: &'static str = r#"
 I like unicorns every second Mondays.
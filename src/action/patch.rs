@@ -0,0 +1,207 @@
+//! Express the picked replacements as unified diff, instead of modifying the files.
+//!
+//! The paths are relative to the project root, see `traverse::project_root`, and
+//! prefixed with `a/` and `b/` respectively, so the result can be fed to `git apply`
+//! from within the project root.
+
+use super::*;
+
+use crate::output::{display_path, relative_to};
+use crate::traverse;
+use similar::TextDiff;
+use std::path::Path;
+
+/// Where the picked replacements end up.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub enum Destination {
+    /// Rewrite the affected files.
+    #[default]
+    InPlace,
+    /// Write a unified diff to the given file or to stdout if `None`.
+    Patch(Option<PathBuf>),
+}
+
+/// The path of a file within the patch, relative to its project root.
+fn patch_path(path: &Path) -> String {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+    path.parent()
+        .map(traverse::project_root)
+        .and_then(|root| relative_to(&root, &path))
+        .unwrap_or_else(|| display_path(&path))
+}
+
+/// Apply `bandaids` to `original` and return the unified diff between both,
+/// empty if nothing changed.
+fn unified_diff(
+    display_path: &str,
+    original: &str,
+    bandaids: impl Iterator<Item = BandAid>,
+) -> Result<String> {
+    let mut corrected = Vec::<u8>::with_capacity(original.len() + 64);
+    correct_lines(
+        bandaids,
+        original
+            .lines()
+            .map(|line| line.to_owned())
+            .enumerate()
+            .map(|(lineno, content)| (lineno + 1, content)),
+        &mut corrected,
+    )?;
+    let mut corrected = String::from_utf8(corrected)?;
+    // `correct_lines` always terminates the last line
+    if !original.ends_with('\n') && corrected.ends_with('\n') {
+        corrected.pop();
+    }
+    if corrected == original {
+        return Ok(String::new());
+    }
+    let diff = TextDiff::from_lines(original, corrected.as_str())
+        .unified_diff()
+        .context_radius(3)
+        .header(
+            format!("a/{}", display_path).as_str(),
+            format!("b/{}", display_path).as_str(),
+        )
+        .to_string();
+    Ok(diff)
+}
+
+impl Action {
+    /// Write the unified diff of all picked bandaids to `sink`, one section per file.
+    pub fn write_changes_as_patch<W: Write>(
+        &self,
+        userpicked: UserPicked,
+        mut sink: W,
+        _config: &Config,
+    ) -> Result<()> {
+        for (path, bandaids) in userpicked.into_files() {
+            let original = fs::read_to_string(&path)
+                .map_err(|e| anyhow!("Failed to read {}", path.display()).context(e))?;
+            let diff = unified_diff(&patch_path(&path), &original, bandaids.into_iter())?;
+            sink.write_all(diff.as_bytes())?;
+        }
+        sink.flush()?;
+        Ok(())
+    }
+
+    /// Either rewrite the files or create a patch, depending on `destination`.
+    pub(super) fn apply(
+        &self,
+        userpicked: UserPicked,
+        destination: &Destination,
+        config: &Config,
+    ) -> Result<()> {
+        match destination {
+            Destination::InPlace => self.write_changes_to_disk(userpicked, config),
+            Destination::Patch(None) => {
+                let stdout = std::io::stdout();
+                self.write_changes_as_patch(userpicked, stdout.lock(), config)
+            }
+            Destination::Patch(Some(path)) => {
+                let file = fs::File::create(path)
+                    .map_err(|e| anyhow!("Failed to create {}", path.display()).context(e))?;
+                self.write_changes_as_patch(userpicked, std::io::BufWriter::new(file), config)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    const ORIGINAL: &str = r#"//! Crate docs.
+
+/// Exampel of a fnuction.
+fn foo() {}
+"#;
+
+    // note the empty context line is a single space
+    const PATCH: &str = "--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,4 +1,4 @@
+ //! Crate docs.
+\x20
+-/// Exampel of a fnuction.
++/// Example of a function.
+ fn foo() {}
+";
+
+    fn bandaids() -> Vec<BandAid> {
+        vec![
+            BandAid {
+                span: (3usize, 4..11).try_into().unwrap(),
+                replacement: "Example".to_owned(),
+            },
+            BandAid {
+                span: (3usize, 17..25).try_into().unwrap(),
+                replacement: "function".to_owned(),
+            },
+        ]
+    }
+
+    #[test]
+    fn patch() {
+        let diff =
+            unified_diff("src/lib.rs", ORIGINAL, bandaids().into_iter()).expect("Diffing works");
+        assert_eq!(diff, PATCH);
+    }
+
+    #[test]
+    fn patch_out_of_order() {
+        // the second one as if it was found within a doc test of the same file
+        let origin = ContentOrigin::RustSourceFile(PathBuf::from("src/lib.rs"));
+        let doc_test = ContentOrigin::RustDocTest(
+            PathBuf::from("src/lib.rs"),
+            (3usize, 4..30).try_into().unwrap(),
+        );
+        let mut picked = UserPicked::default();
+        let mut bandaids = bandaids().into_iter();
+        let first = bandaids.next().unwrap();
+        picked.add_bandaid(&doc_test, bandaids.next().unwrap());
+        picked.add_bandaid(&origin, first);
+        let files = picked.into_files();
+        assert_eq!(files.len(), 1);
+        let (_path, bandaids) = files.into_iter().next().unwrap();
+        let diff =
+            unified_diff("src/lib.rs", ORIGINAL, bandaids.into_iter()).expect("Diffing works");
+        assert_eq!(diff, PATCH);
+    }
+
+    #[test]
+    fn patch_missing_trailing_newline() {
+        let original = ORIGINAL.trim_end();
+        let diff =
+            unified_diff("src/lib.rs", original, bandaids().into_iter()).expect("Diffing works");
+        assert!(diff.ends_with("fn foo() {}\n\\ No newline at end of file\n"));
+    }
+
+    #[test]
+    fn patch_unchanged() {
+        let diff = unified_diff("src/lib.rs", ORIGINAL, std::iter::empty()).expect("Diffing works");
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn patch_outside_cwd() {
+        let dir =
+            std::env::temp_dir().join(format!("cargo-spellcheck-patch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"patch\"\n").unwrap();
+        fs::write(dir.join("src/lib.rs"), ORIGINAL).unwrap();
+
+        let origin = ContentOrigin::RustSourceFile(dir.join("src/lib.rs"));
+        let mut picked = UserPicked::default();
+        for bandaid in bandaids() {
+            picked.add_bandaid(&origin, bandaid);
+        }
+        let mut patch = Vec::new();
+        Action::Fix(Destination::Patch(None))
+            .write_changes_as_patch(picked, &mut patch, &Config::default())
+            .expect("Writing the patch works");
+        assert_eq!(String::from_utf8(patch).unwrap(), PATCH);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,292 @@
+//! Re-wrap the paragraphs of doc comments to a maximum line length.
+//!
+//! Only paragraphs are re-wrapped, all other markdown blocks such as lists,
+//! code blocks, headings and tables keep their line breaks. The re-wrapped lines
+//! keep the `///` or `//!` prefix and the indentation of the paragraph's first line.
+
+use super::*;
+
+use pulldown_cmark::{Event, Options, Parser, Tag};
+
+/// Whether `word` would start a new markdown block when placed at the start of a line,
+/// i.e. a list item, a heading or a block quote, instead of continuing the paragraph.
+fn starts_block(word: &str) -> bool {
+    let ordered = word
+        .strip_suffix(['.', ')'])
+        .map(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+        .unwrap_or(false);
+    ordered
+        || matches!(word, "-" | "*" | "+")
+        || word.starts_with(['#', '>', '|'])
+        || word.starts_with("```")
+        || word.starts_with("~~~")
+        || word.chars().all(|c| c == '=')
+        || word.chars().all(|c| c == '-')
+}
+
+/// Greedily distribute `words` over lines of at most `width` chars.
+///
+/// Words are never split, so a single word exceeding `width` ends up on a line of its own.
+fn wrap<'a>(words: impl IntoIterator<Item = &'a str>, width: usize) -> Vec<String> {
+    let mut acc = Vec::new();
+    let mut line = String::new();
+    for word in words {
+        let len = line.chars().count() + 1 + word.chars().count();
+        if !line.is_empty() && len > width && !starts_block(word) {
+            acc.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        acc.push(line);
+    }
+    acc
+}
+
+/// The line ranges, as indices of the lines of `content`, of all paragraphs
+/// which can be re-wrapped.
+///
+/// Paragraphs within lists, block quotes, tables or footnotes as well as paragraphs
+/// with hard line breaks or inline html are left as they are.
+fn paragraphs(content: &str) -> Vec<std::ops::RangeInclusive<usize>> {
+    let line_of = |offset: usize| content[..offset].matches('\n').count();
+
+    let mut acc = Vec::new();
+    let mut nested = 0usize;
+    let mut paragraph: Option<(Range, bool)> = None;
+    for (event, offset) in Parser::new_ext(content, Options::all()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Item)
+            | Event::Start(Tag::BlockQuote)
+            | Event::Start(Tag::Table(_))
+            | Event::Start(Tag::FootnoteDefinition(_)) => nested += 1,
+            Event::End(Tag::Item)
+            | Event::End(Tag::BlockQuote)
+            | Event::End(Tag::Table(_))
+            | Event::End(Tag::FootnoteDefinition(_)) => nested = nested.saturating_sub(1),
+            Event::Start(Tag::Paragraph) if nested == 0 => paragraph = Some((offset, true)),
+            Event::HardBreak | Event::Html(_) => {
+                if let Some((_, ref mut reflowable)) = paragraph {
+                    *reflowable = false;
+                }
+            }
+            Event::End(Tag::Paragraph) => {
+                if let Some((range, true)) = paragraph.take() {
+                    let first = line_of(range.start);
+                    let last = line_of(content[..range.end].trim_end().len());
+                    acc.push(first..=last);
+                }
+            }
+            _ => {}
+        }
+    }
+    acc
+}
+
+/// Re-wrap the paragraphs of `chunk`, which originates from the Rust source `lines`.
+///
+/// Returns one bandaid per changed paragraph, replacing all of its lines. Chunks
+/// not solely consisting of `///` or `//!` comments are skipped.
+fn reflow_chunk(chunk: &CheckableChunk, lines: &[String], max_line_length: usize) -> Vec<BandAid> {
+    let first_line = match chunk.iter().next() {
+        Some((_range, span)) => span.start.line,
+        None => return Vec::new(),
+    };
+    let content = chunk.as_str().split('\n').collect::<Vec<_>>();
+
+    // the comment prefix of every line of the chunk, including its indentation
+    let mut prefixes = Vec::with_capacity(content.len());
+    for (idx, content) in content.iter().enumerate() {
+        let source = match lines.get(first_line + idx - 1) {
+            Some(source) if source.ends_with(content) => source,
+            _ => return Vec::new(),
+        };
+        let prefix = &source[..source.len() - content.len()];
+        if !matches!(prefix.trim_start(), "///" | "//!") {
+            return Vec::new();
+        }
+        prefixes.push(prefix);
+    }
+
+    let mut acc = Vec::new();
+    for paragraph in paragraphs(chunk.as_str()) {
+        let (first, last) = (*paragraph.start(), *paragraph.end());
+        let head = content[first];
+        let indent = &head[..head.len() - head.trim_start().len()];
+        let lead = format!("{}{}", prefixes[first], indent);
+        let width = max_line_length.saturating_sub(lead.chars().count());
+
+        let words = content[paragraph.clone()]
+            .iter()
+            .flat_map(|line| line.split_whitespace());
+        let wrapped = wrap(words, width)
+            .into_iter()
+            .map(|line| format!("{}{}", lead, line))
+            .collect::<Vec<_>>();
+
+        let original = &lines[(first_line + first - 1)..(first_line + last)];
+        if wrapped.as_slice() == original {
+            continue;
+        }
+        let last_len = original
+            .last()
+            .map(|line| line.chars().count())
+            .unwrap_or(0);
+        trace!(
+            "Re-wrapping lines {}..={} into {} lines",
+            first_line + first,
+            first_line + last,
+            wrapped.len()
+        );
+        acc.push(BandAid {
+            span: Span {
+                start: LineColumn {
+                    line: first_line + first,
+                    column: 0,
+                },
+                end: LineColumn {
+                    line: first_line + last,
+                    column: last_len.saturating_sub(1),
+                },
+            },
+            replacement: wrapped.join("\n"),
+        });
+    }
+    acc
+}
+
+impl Action {
+    /// Re-wrap the paragraphs of all doc comments of `documentation` to the
+    /// configured line length and write the result to `destination`.
+    pub(super) fn reflow(
+        &self,
+        documentation: &Documentation,
+        destination: &Destination,
+        config: &Config,
+    ) -> Result<Finish> {
+        let max_line_length = config.reflow.clone().unwrap_or_default().max_line_length();
+
+        let mut picked = UserPicked::default();
+        for (origin, chunks) in documentation.iter() {
+            let path = match origin {
+                ContentOrigin::RustSourceFile(path) => path,
+                _ => continue,
+            };
+            let source = fs::read_to_string(path)
+                .map_err(|e| anyhow!("Failed to read {}", path.display()).context(e))?;
+            let lines = source
+                .lines()
+                .map(|line| line.to_owned())
+                .collect::<Vec<_>>();
+            let mut bandaids = chunks
+                .iter()
+                .flat_map(|chunk| reflow_chunk(chunk, &lines, max_line_length))
+                .collect::<Vec<_>>();
+            if bandaids.is_empty() {
+                continue;
+            }
+            bandaids.sort_by_key(|bandaid| bandaid.span.start.line);
+            // a file reachable by multiple paths contributes its chunks repeatedly
+            bandaids.dedup();
+            picked.bandaids.insert(origin.clone(), bandaids);
+        }
+
+        let n = picked.total_count();
+        debug!("Re-wrapping {} paragraphs", n);
+        self.apply(picked, destination, config)?;
+        Ok(Finish::MistakeCount(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"    /// A rather long paragraph, which does by far exceed the configured maximum line length and thus needs
+    /// to be
+    /// re-wrapped.
+    ///
+    /// # A heading which is much too long, but must not be re-wrapped under any circumstances
+    ///
+    /// - a list item which is too long, yet remains untouched by re-wrapping as well
+    ///
+    /// ```rust
+    /// let code = "stays as it is, no matter how long the line";
+    /// ```
+    ///
+    /// Short lines
+    /// are joined.
+    fn foo() {}
+"#;
+
+    const REFLOWED: &str = r#"    /// A rather long paragraph, which does by far exceed the configured maximum
+    /// line length and thus needs to be re-wrapped.
+    ///
+    /// # A heading which is much too long, but must not be re-wrapped under any circumstances
+    ///
+    /// - a list item which is too long, yet remains untouched by re-wrapping as well
+    ///
+    /// ```rust
+    /// let code = "stays as it is, no matter how long the line";
+    /// ```
+    ///
+    /// Short lines are joined.
+    fn foo() {}
+"#;
+
+    fn reflowed(source: &str, max_line_length: usize) -> String {
+        let origin = ContentOrigin::RustSourceFile(PathBuf::from("/tmp/reflow.rs"));
+        let docs = Documentation::from((origin.clone(), source));
+        let lines = source
+            .lines()
+            .map(|line| line.to_owned())
+            .collect::<Vec<_>>();
+        let bandaids = docs
+            .iter()
+            .filter(|(chunk_origin, _chunks)| **chunk_origin == origin)
+            .flat_map(|(_origin, chunks)| chunks.iter())
+            .flat_map(|chunk| reflow_chunk(chunk, &lines, max_line_length))
+            .collect::<Vec<_>>();
+        let mut sink = Vec::new();
+        correct_lines(
+            bandaids.into_iter(),
+            lines
+                .into_iter()
+                .enumerate()
+                .map(|(idx, line)| (idx + 1, line)),
+            &mut sink,
+        )
+        .unwrap();
+        String::from_utf8(sink).unwrap()
+    }
+
+    #[test]
+    fn wrapping() {
+        assert_eq!(
+            wrap("a bb ccc dddd".split(' '), 6),
+            vec!["a bb", "ccc", "dddd"]
+        );
+        assert_eq!(
+            wrap("overlong word".split(' '), 4),
+            vec!["overlong", "word"]
+        );
+        // never start a list item
+        assert_eq!(wrap("items - are".split(' '), 5), vec!["items -", "are"]);
+    }
+
+    #[test]
+    fn reflow_paragraphs() {
+        assert_eq!(reflowed(SOURCE, 80), REFLOWED);
+        // idempotent
+        assert_eq!(reflowed(REFLOWED, 80), REFLOWED);
+    }
+
+    #[test]
+    fn reflow_skips_block_comments() {
+        const BLOCK: &str = "/** A block comment with a paragraph that is way too long for forty columns. */\nfn foo() {}\n";
+        assert_eq!(reflowed(BLOCK, 40), BLOCK);
+    }
+}
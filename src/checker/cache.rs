@@ -0,0 +1,279 @@
+//! Persistent cache of checker results
+//!
+//! Stores the findings of every checked chunk, keyed by its origin and content,
+//! in `target/spellcheck/`. One file exists per `Detector`, named after the
+//! fingerprint of the checker configuration, so any change of the configuration
+//! or of the referenced dictionaries and word lists starts from scratch.
+//!
+//! Only chunk relative ranges are stored, the spans are recovered from the
+//! current chunk, so moving a doc comment within its file does not invalidate its entry.
+//!
+//! Checkers with findings depending on anything else must not use the cache at all.
+
+#![cfg_attr(
+    not(any(feature = "hunspell", feature = "languagetool")),
+    allow(unused)
+)]
+
+use crate::documentation::{CheckableChunk, ContentOrigin};
+use crate::util::fnv1a64;
+use crate::{Detector, Range, Suggestion};
+
+use anyhow::{anyhow, Result};
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// The default cache directory, `target/spellcheck` of the enclosing workspace.
+///
+/// Respects `CARGO_TARGET_DIR`, the workspace is the closest directory with a `Cargo.lock`.
+pub fn default_dir(cwd: &Path) -> PathBuf {
+    let target = std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            cwd.ancestors()
+                .find(|dir| dir.join("Cargo.lock").is_file())
+                .unwrap_or(cwd)
+                .join("target")
+        });
+    target.join("spellcheck")
+}
+
+/// Fingerprint of a checker configuration, including the state of all `files` it references.
+///
+/// For the files only size and modification time are considered, which is sufficient
+/// to detect edits and far cheaper than hashing whole dictionaries.
+pub(crate) fn fingerprint<C: Serialize>(config: &C, files: &[PathBuf]) -> Result<u64> {
+    let mut fingerprint = format!("{}\n", env!("CARGO_PKG_VERSION"));
+    fingerprint.push_str(&serde_json::to_string(config)?);
+    for path in files {
+        fingerprint.push('\n');
+        fingerprint.push_str(&path.display().to_string());
+        if let Ok(meta) = fs::metadata(path) {
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|duration| duration.as_nanos())
+                .unwrap_or_default();
+            fingerprint.push_str(&format!(":{}:{}", meta.len(), modified));
+        }
+    }
+    Ok(fnv1a64(fingerprint.as_bytes()))
+}
+
+/// A finding without its location, which is recovered from the chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedSuggestion {
+    range: Range,
+    replacements: Vec<String>,
+    description: Option<String>,
+}
+
+/// Cached findings of a single detector.
+#[derive(Debug)]
+pub(crate) struct Cache {
+    detector: Detector,
+    path: PathBuf,
+    entries: Mutex<HashMap<u64, Vec<CachedSuggestion>>>,
+    dirty: AtomicBool,
+}
+
+impl Cache {
+    fn file_prefix(detector: Detector) -> String {
+        format!("{}-", detector.to_string().to_lowercase())
+    }
+
+    /// Load the cache of `detector` with the given configuration `fingerprint` from `dir`.
+    ///
+    /// A missing or unreadable cache file is equivalent to an empty cache.
+    pub(crate) fn load(dir: &Path, detector: Detector, fingerprint: u64) -> Self {
+        let path = dir.join(format!(
+            "{}{:016x}.json",
+            Self::file_prefix(detector),
+            fingerprint
+        ));
+        let entries = fs::read(&path)
+            .ok()
+            .and_then(|content| {
+                serde_json::from_slice::<HashMap<u64, Vec<CachedSuggestion>>>(&content)
+                    .map_err(|e| debug!("Discarding corrupt cache {}: {}", path.display(), e))
+                    .ok()
+            })
+            .unwrap_or_default();
+        debug!(
+            "Loaded {} cached chunks from {}",
+            entries.len(),
+            path.display()
+        );
+        Self {
+            detector,
+            path,
+            entries: Mutex::new(entries),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Checkers receive the origin along with the chunk, so both are part of the key.
+    fn key(origin: &ContentOrigin, chunk: &CheckableChunk) -> u64 {
+        fnv1a64(format!("{}\0{:016x}", origin, chunk.content_hash()).as_bytes())
+    }
+
+    /// Obtain the findings of a previous run for `chunk`, if any.
+    pub(crate) fn lookup<'s>(
+        &self,
+        origin: &ContentOrigin,
+        chunk: &'s CheckableChunk,
+    ) -> Option<Vec<Suggestion<'s>>> {
+        let entries = self
+            .entries
+            .lock()
+            .expect("Cache lock is never poisoned. qed");
+        let cached = entries.get(&Self::key(origin, chunk))?;
+        trace!("Cache hit for chunk in {}", origin.as_path().display());
+        Some(
+            cached
+                .iter()
+                .flat_map(|cached| {
+                    chunk
+                        .find_spans(cached.range.clone())
+                        .into_iter()
+                        .map(move |(range, span)| Suggestion {
+                            detector: self.detector,
+                            origin: origin.clone(),
+                            chunk,
+                            range,
+                            span,
+                            replacements: cached.replacements.clone(),
+                            description: cached.description.clone(),
+                        })
+                })
+                .collect(),
+        )
+    }
+
+    /// Record the findings for `chunk`, which may be none at all.
+    pub(crate) fn insert<'a, 's: 'a>(
+        &self,
+        origin: &ContentOrigin,
+        chunk: &CheckableChunk,
+        suggestions: impl IntoIterator<Item = &'a Suggestion<'s>>,
+    ) {
+        let cached = suggestions
+            .into_iter()
+            .map(|suggestion| CachedSuggestion {
+                range: suggestion.range.clone(),
+                replacements: suggestion.replacements.clone(),
+                description: suggestion.description.clone(),
+            })
+            .collect();
+        self.entries
+            .lock()
+            .expect("Cache lock is never poisoned. qed")
+            .insert(Self::key(origin, chunk), cached);
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Write the cache back to disk if anything changed, and remove
+    /// caches of the same detector with outdated fingerprints.
+    pub(crate) fn store(&self) -> Result<()> {
+        if !self.dirty.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let dir = self
+            .path
+            .parent()
+            .expect("Cache path is always joined to a directory. qed");
+        fs::create_dir_all(dir).map_err(|e| {
+            anyhow!("Failed to create cache directory {}", dir.display()).context(e)
+        })?;
+
+        let prefix = Self::file_prefix(self.detector);
+        for entry in fs::read_dir(dir)?.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let stale = path != self.path
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map(|name| name.starts_with(&prefix) && name.ends_with(".json"))
+                    .unwrap_or(false);
+            if stale {
+                if let Err(e) = fs::remove_file(&path) {
+                    warn!("Failed to remove outdated cache {}: {}", path.display(), e);
+                }
+            }
+        }
+
+        let entries = self
+            .entries
+            .lock()
+            .expect("Cache lock is never poisoned. qed");
+        let content = serde_json::to_vec(&*entries)?;
+        // write to a temporary file first, concurrent runs must never observe a partial cache
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, content)
+            .map_err(|e| anyhow!("Failed to write cache {}", tmp.display()).context(e))?;
+        fs::rename(&tmp, &self.path)
+            .map_err(|e| anyhow!("Failed to write cache {}", self.path.display()).context(e))?;
+        debug!(
+            "Stored {} cached chunks in {}",
+            entries.len(),
+            self.path.display()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::tests::{chunk, suggestion};
+
+    #[test]
+    fn roundtrip() {
+        let dir =
+            std::env::temp_dir().join(format!("cargo-spellcheck-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let chunk = chunk();
+        let origin = ContentOrigin::TestEntity;
+
+        let cache = Cache::load(&dir, Detector::Dummy, 7);
+        assert!(cache.lookup(&origin, &chunk).is_none());
+        let expected = suggestion(&chunk);
+        cache.insert(&origin, &chunk, vec![&expected]);
+        cache.store().unwrap();
+
+        // a stale cache of a different configuration is removed
+        let stale = Cache::load(&dir, Detector::Dummy, 8);
+        stale.insert(&origin, &chunk, vec![]);
+        stale.store().unwrap();
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        let stale = Cache::load(&dir, Detector::Dummy, 8);
+        assert!(stale.lookup(&origin, &chunk).unwrap().is_empty());
+
+        let cache = Cache::load(&dir, Detector::Dummy, 7);
+        assert!(cache.lookup(&origin, &chunk).is_none());
+        cache.insert(&origin, &chunk, vec![&expected]);
+        cache.store().unwrap();
+        let cache = Cache::load(&dir, Detector::Dummy, 7);
+        let cached = cache.lookup(&origin, &chunk).unwrap();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].range, expected.range);
+        assert_eq!(
+            Some(&cached[0].span),
+            chunk.find_spans(expected.range.clone()).values().next()
+        );
+        assert_eq!(cached[0].replacements, expected.replacements);
+
+        // the same content in another file is checked again
+        let other = ContentOrigin::RustSourceFile(PathBuf::from("src/other.rs"));
+        assert!(cache.lookup(&other, &chunk).is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
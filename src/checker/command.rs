@@ -0,0 +1,235 @@
+//! Findings of an external program
+//!
+//! The plain text of every chunk, with the markdown erased, is passed as a JSON
+//! object on stdin of the configured program:
+//!
+//! ```json
+//! {"origin": "src/lib.rs", "text": "A sentence with teh mistake."}
+//! ```
+//!
+//! The program prints a JSON list of findings on stdout. The `range` is given in
+//! chars of `text`, the `end` is exclusive. `replacements` and `message` are optional.
+//!
+//! ```json
+//! [{"range": {"start": 16, "end": 19}, "replacements": ["the"], "message": "Typo"}]
+//! ```
+//!
+//! A non-zero exit status fails the whole check, empty output means no findings.
+//!
+//! The findings are never cached, there is no telling which files the program reads.
+
+use super::{pool, Checker, Detector, Sink, Suggestion};
+
+use crate::config::CommandConfig;
+use crate::documentation::{CheckableChunk, ContentOrigin, PlainOverlay};
+use crate::{Documentation, Range, SuggestionSet};
+
+use anyhow::{anyhow, bail, Result};
+use log::{debug, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, Serialize)]
+struct Input<'a> {
+    origin: String,
+    text: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct Finding {
+    range: Range,
+    #[serde(default)]
+    replacements: Vec<String>,
+    message: Option<String>,
+}
+
+pub(crate) struct CommandChecker;
+
+impl CommandChecker {
+    /// Run the program on a single `text` and parse its findings.
+    fn run(config: &CommandConfig, origin: &ContentOrigin, text: &str) -> Result<Vec<Finding>> {
+        let input = serde_json::to_vec(&Input {
+            origin: origin.to_string(),
+            text,
+        })?;
+        let program = config.program.display();
+        let mut child = Command::new(&config.program)
+            .args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("Failed to run `{}`", program).context(e))?;
+
+        // write from a separate thread, the program might print before reading everything
+        let mut stdin = child
+            .stdin
+            .take()
+            .expect("Stdin is piped, so it is always present. qed");
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| anyhow!("Failed to wait for `{}`", program).context(e))?;
+        if let Ok(Err(e)) = writer.join() {
+            // the program is free to not read its input at all
+            debug!("Failed to pass the text to `{}`: {}", program, e);
+        }
+        if !output.status.success() {
+            bail!(
+                "`{}` failed with {}: {}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        parse_findings(&output.stdout)
+            .map_err(|e| anyhow!("Failed to parse the output of `{}`", program).context(e))
+    }
+}
+
+/// Parse the findings printed by the program.
+fn parse_findings(stdout: &[u8]) -> Result<Vec<Finding>> {
+    if stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_slice(stdout)?)
+}
+
+/// Convert the findings within `plain` to suggestions.
+fn suggestions<'a, 's>(
+    origin: &ContentOrigin,
+    chunk: &'a CheckableChunk,
+    plain: &PlainOverlay,
+    findings: Vec<Finding>,
+) -> Vec<Suggestion<'s>>
+where
+    'a: 's,
+{
+    let len = plain.as_str().chars().count();
+    let mut acc = Vec::with_capacity(findings.len());
+    for finding in findings {
+        if finding.range.start >= finding.range.end || finding.range.end > len {
+            warn!(
+                "Ignoring finding with range {:?} outside of the text with {} chars",
+                finding.range, len
+            );
+            continue;
+        }
+        for (range, span) in plain.find_spans(finding.range) {
+            acc.push(Suggestion {
+                detector: Detector::Command,
+                origin: origin.clone(),
+                chunk,
+                span,
+                range,
+                replacements: finding.replacements.clone(),
+                description: finding.message.clone(),
+            });
+        }
+    }
+    acc
+}
+
+impl Checker for CommandChecker {
+    type Config = CommandConfig;
+    type Worker = ();
+
+    const DETECTOR: Detector = Detector::Command;
+
+    fn worker(_config: &Self::Config) -> Result<Self::Worker> {
+        Ok(())
+    }
+
+    fn check_chunks<'a, 's>(
+        _worker: &mut Self::Worker,
+        origin: &ContentOrigin,
+        chunks: &[&'a CheckableChunk],
+        config: &Self::Config,
+    ) -> Result<Vec<Suggestion<'s>>>
+    where
+        'a: 's,
+    {
+        let mut acc = Vec::with_capacity(chunks.len());
+        for &chunk in chunks {
+            let plain = chunk.erase_markdown();
+            if plain.as_str().trim().is_empty() {
+                continue;
+            }
+            trace!("Passing {:?} to `{}`", &plain, config.program.display());
+            let findings = Self::run(config, origin, plain.as_str())?;
+            acc.extend(suggestions(origin, chunk, &plain, findings));
+        }
+        Ok(acc)
+    }
+
+    /// Never cached, the findings depend on whatever the program reads.
+    fn check_with_sink<'a, 's>(
+        docu: &'a Documentation,
+        config: &Self::Config,
+        jobs: usize,
+        _cache_dir: Option<&Path>,
+        sink: &mut Sink<'_>,
+    ) -> Result<SuggestionSet<'s>>
+    where
+        'a: 's,
+    {
+        pool::check::<Self>(docu, config, jobs, None, sink)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parse() {
+        assert!(parse_findings(b" \n").unwrap().is_empty());
+        let findings = parse_findings(
+            br#"[{"range": {"start": 2, "end": 5}, "replacements": ["the"]}, {"range": {"start": 0, "end": 1}, "message": "Capital"}]"#,
+        )
+        .unwrap();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].range, 2..5);
+        assert_eq!(findings[0].replacements, vec!["the".to_owned()]);
+        assert_eq!(findings[1].message.as_deref(), Some("Capital"));
+        assert!(parse_findings(b"{}").is_err());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn external_program() {
+        const SOURCE: &str = "/// Finds *teh* mistake.\nstruct Foo;\n";
+        // reports `teh`, but only if the text was passed on stdin
+        let script = r#"if grep -q '"text":"Finds teh mistake."'; then
+    echo '[{"range": {"start": 6, "end": 9}, "replacements": ["the"], "message": "Typo"}]'
+fi"#;
+        let config = CommandConfig {
+            program: PathBuf::from("sh"),
+            args: vec!["-c".to_owned(), script.to_owned()],
+        };
+        let origin = ContentOrigin::RustSourceFile(PathBuf::from("src/foo.rs"));
+        let docs = Documentation::from((origin, SOURCE));
+        let suggestions = CommandChecker::check(&docs, &config, 1, None).unwrap();
+        let (_origin, suggestions) = suggestions.iter().next().expect("Must find the typo");
+        assert_eq!(suggestions.len(), 1);
+        let suggestion = &suggestions[0];
+        assert_eq!(suggestion.detector, Detector::Command);
+        assert_eq!(
+            crate::util::sub_chars(suggestion.chunk.as_str(), suggestion.range.clone()),
+            "teh"
+        );
+        assert_eq!(suggestion.span.start.line, 1);
+        assert_eq!(suggestion.span.start.column, 11);
+        assert_eq!(suggestion.description.as_deref(), Some("Typo"));
+
+        let failing = CommandConfig {
+            program: PathBuf::from("sh"),
+            args: vec!["-c".to_owned(), "echo broken >&2; exit 3".to_owned()],
+        };
+        assert!(CommandChecker::check(&docs, &failing, 1, None).is_err());
+    }
+}
@@ -4,8 +4,8 @@
 
 use super::tokenize;
 use super::Checker;
-use crate::documentation::Documentation;
-use crate::suggestion::{Detector, Suggestion, SuggestionSet};
+use crate::documentation::{CheckableChunk, ContentOrigin};
+use crate::suggestion::{Detector, Suggestion};
 use crate::util::sub_chars;
 use anyhow::Result;
 use log::trace;
@@ -15,46 +15,52 @@ pub struct DummyChecker;
 
 impl Checker for DummyChecker {
     type Config = ();
+    type Worker = ();
 
-    fn check<'a, 's>(docu: &'a Documentation, _: &Self::Config) -> Result<SuggestionSet<'s>>
+    const DETECTOR: Detector = Detector::Dummy;
+
+    fn worker(_: &Self::Config) -> Result<Self::Worker> {
+        Ok(())
+    }
+
+    fn check_chunks<'a, 's>(
+        _: &mut Self::Worker,
+        origin: &ContentOrigin,
+        chunks: &[&'a CheckableChunk],
+        _: &Self::Config,
+    ) -> Result<Vec<Suggestion<'s>>>
     where
         'a: 's,
     {
-        let suggestions = docu.iter().try_fold::<SuggestionSet, _, Result<_>>(
-            SuggestionSet::new(),
-            |mut acc, (origin, chunks)| {
-                let chunk = chunks
-                    .iter()
-                    .next()
-                    .expect("DummyChecker expects at least one chunk");
-                let plain = chunk.erase_markdown();
-                for (index, range) in dbg!(tokenize(plain.as_str())).into_iter().enumerate() {
-                    trace!("Token: >{}<", sub_chars(plain.as_str(), range.clone()));
-                    let detector = Detector::Dummy;
-                    let range2span = plain.find_spans(range.clone());
-                    for (range, span) in range2span {
-                        trace!(
-                            "Suggestion for {:?} -> {}",
-                            range,
-                            chunk.display(range.clone())
-                        );
-                        let replacements = vec![format!("replacement_{}", index)];
-                        let suggestion = Suggestion {
-                            detector,
-                            span,
-                            range,
-                            origin: origin.clone(),
-                            replacements,
-                            chunk,
-                            description: None,
-                        };
-                        acc.add(origin.clone(), suggestion);
-                    }
-                }
-                Ok(acc)
-            },
-        )?;
-
-        Ok(suggestions)
+        let mut acc = Vec::new();
+        let chunk = *chunks
+            .iter()
+            .next()
+            .expect("DummyChecker expects at least one chunk");
+        let plain = chunk.erase_markdown();
+        for (index, range) in dbg!(tokenize(plain.as_str())).into_iter().enumerate() {
+            trace!("Token: >{}<", sub_chars(plain.as_str(), range.clone()));
+            let detector = Self::DETECTOR;
+            let range2span = plain.find_spans(range.clone());
+            for (range, span) in range2span {
+                trace!(
+                    "Suggestion for {:?} -> {}",
+                    range,
+                    chunk.display(range.clone())
+                );
+                let replacements = vec![format!("replacement_{}", index)];
+                let suggestion = Suggestion {
+                    detector,
+                    span,
+                    range,
+                    origin: origin.clone(),
+                    replacements,
+                    chunk,
+                    description: None,
+                };
+                acc.push(suggestion);
+            }
+        }
+        Ok(acc)
     }
 }
@@ -0,0 +1,149 @@
+//! The dictionary embedded into the binary
+//!
+//! Used as fallback if no `<lang>.dic` and `<lang>.aff` is found in any search dir.
+//! Both files are vendored gzip compressed in `dictionaries/`, see its `README.md`.
+
+use anyhow::{anyhow, Result};
+use flate2::read::GzDecoder;
+use std::io::Read;
+
+/// Language of the embedded dictionary.
+pub(crate) const LANG: &str = "en_US";
+const AFF: &[u8] = include_bytes!("../../dictionaries/en_US.aff.gz");
+const DIC: &[u8] = include_bytes!("../../dictionaries/en_US.dic.gz");
+
+/// Whether a dictionary for `lang` is embedded.
+pub(crate) fn provides(lang: &str) -> bool {
+    lang == LANG
+}
+
+fn decompress(compressed: &[u8]) -> Result<Vec<u8>> {
+    let mut content = Vec::with_capacity(compressed.len() * 3);
+    GzDecoder::new(compressed)
+        .read_to_end(&mut content)
+        .map_err(|e| anyhow!("Failed to decompress the embedded dictionary").context(e))?;
+    Ok(content)
+}
+
+/// Content of the embedded `.aff` file.
+pub(crate) fn aff() -> Result<Vec<u8>> {
+    decompress(AFF)
+}
+
+/// Content of the embedded `.dic` file.
+pub(crate) fn dic() -> Result<Vec<u8>> {
+    decompress(DIC)
+}
+
+/// Files of the embedded dictionary, for libraries which only read dictionaries from disk.
+#[cfg(any(feature = "hunspell", test))]
+mod files {
+    #[cfg(feature = "hunspell")]
+    use super::{aff, dic, LANG};
+    #[cfg(feature = "hunspell")]
+    use crate::Config;
+
+    use anyhow::{anyhow, Result};
+    use log::debug;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    #[cfg(feature = "hunspell")]
+    use std::sync::Mutex;
+
+    /// Write `content` to `path`, unless it is already there.
+    ///
+    /// Another process might read the file concurrently, so it is replaced atomically.
+    fn write_if_changed(path: &Path, content: &[u8]) -> Result<()> {
+        if fs::read(path)
+            .map(|existing| existing == content)
+            .unwrap_or(false)
+        {
+            debug!(
+                "Embedded dictionary is already present at {}",
+                path.display()
+            );
+            return Ok(());
+        }
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&tmp, content)
+            .and_then(|_| fs::rename(&tmp, path))
+            .map_err(|e| anyhow!("Failed to write {}", path.display()).context(e))
+    }
+
+    /// Write the embedded dictionary to `dir`, returns the paths of the `.dic` and `.aff` file.
+    pub(super) fn write_to(
+        dir: &Path,
+        lang: &str,
+        aff: &[u8],
+        dic: &[u8],
+    ) -> Result<(PathBuf, PathBuf)> {
+        fs::create_dir_all(dir)
+            .map_err(|e| anyhow!("Failed to create directory {}", dir.display()).context(e))?;
+        let aff_path = dir.join(lang).with_extension("aff");
+        let dic_path = dir.join(lang).with_extension("dic");
+        write_if_changed(&aff_path, aff)?;
+        write_if_changed(&dic_path, dic)?;
+        Ok((dic_path, aff_path))
+    }
+
+    /// Directory the embedded dictionary is written to for libraries which only read files.
+    #[cfg(feature = "hunspell")]
+    fn cache_dir() -> PathBuf {
+        directories::ProjectDirs::from(Config::QUALIFIER, Config::ORGANIZATION, Config::APPLICATION)
+            .map(|dirs| dirs.cache_dir().to_owned())
+            .unwrap_or_else(|| std::env::temp_dir().join(Config::APPLICATION))
+            .join("dictionaries")
+    }
+
+    /// Write the embedded dictionary to the cache dir, once per process.
+    ///
+    /// Returns the paths of the `.dic` and `.aff` file.
+    #[cfg(feature = "hunspell")]
+    pub(crate) fn to_files() -> Result<(PathBuf, PathBuf)> {
+        lazy_static::lazy_static! {
+            static ref WRITTEN: Mutex<Option<(PathBuf, PathBuf)>> = Mutex::new(None);
+        }
+        // all workers initialize at the same time
+        let mut written = WRITTEN.lock().expect("Writing never panics. qed");
+        if let Some(ref paths) = *written {
+            return Ok(paths.clone());
+        }
+        let paths = write_to(&cache_dir(), LANG, &aff()?, &dic()?)?;
+        *written = Some(paths.clone());
+        Ok(paths)
+    }
+}
+
+#[cfg(feature = "hunspell")]
+pub(crate) use self::files::to_files;
+
+#[cfg(test)]
+mod tests {
+    use super::files::write_to;
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::fs;
+    use std::io::Write;
+
+    #[test]
+    fn roundtrip() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(b"SET UTF-8\n").unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert_eq!(decompress(&compressed).unwrap(), b"SET UTF-8\n".to_vec());
+        assert!(provides("en_US"));
+        assert!(!provides("de_DE"));
+        assert!(dic().unwrap().starts_with(b"122318\n"));
+
+        let dir =
+            std::env::temp_dir().join(format!("cargo-spellcheck-embedded-{}", std::process::id()));
+        let (dic, aff) = write_to(&dir, "xx_YY", b"SET UTF-8\n", b"1\nfoo\n").unwrap();
+        assert_eq!(dic, dir.join("xx_YY.dic"));
+        assert_eq!(fs::read(&aff).unwrap(), b"SET UTF-8\n".to_vec());
+        // unchanged files are kept
+        write_to(&dir, "xx_YY", b"SET UTF-8\n", b"1\nfoo\n").unwrap();
+        write_to(&dir, "xx_YY", b"SET UTF-8\n", b"1\nbar\n").unwrap();
+        assert_eq!(fs::read(&dic).unwrap(), b"1\nbar\n".to_vec());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
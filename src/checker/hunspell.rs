@@ -4,75 +4,45 @@
 //! and checks the individual tokens against the dictionary using
 //! the defined affixes.
 //! Can handle multiple dictionaries.
+//! Falls back to the embedded dictionary, if none is found in the search dirs.
 
-use super::{tokenize, Checker, Detector, Documentation, Suggestion, SuggestionSet};
+use super::{
+    check_words, dictionary_files, embedded, find_dictionary, wordlist, Checker, Detector,
+    Suggestion,
+};
 
-use crate::documentation::{CheckableChunk, ContentOrigin, PlainOverlay};
-use crate::util::sub_chars;
-use crate::Range;
-use log::{debug, trace};
+use crate::documentation::{CheckableChunk, ContentOrigin};
+use log::{info, trace, warn};
 use std::path::PathBuf;
 
 use hunspell_rs::Hunspell;
 
-use anyhow::{anyhow, bail, Result};
-
-use super::quirks::{
-    replacements_contain_dashed, replacements_contain_dashless, transform, Transformed,
-};
+use anyhow::{bail, Result};
 
 pub struct HunspellChecker;
 
 impl HunspellChecker {
     fn inner_init(config: &<Self as Checker>::Config) -> Result<Hunspell> {
-        let search_dirs = config.search_dirs();
-
         let lang = config.lang();
 
-        // lookup paths are really just an attempt to provide a dictionary, so be more forgiving
-        // when encountering errors here
-        let (dic, aff): (PathBuf, PathBuf) = search_dirs
-            .into_iter()
-            .filter(|search_dir| {
-                let keep = search_dir.is_dir();
-                if !keep {
-                    // search_dir also contains the default paths, so just silently ignore these
-                    debug!(
-                        "Dictionary search path is not a directory {}",
-                        search_dir.display()
-                    );
-                } else {
-                    debug!(
-                        "Found dictionary search path {}",
-                        search_dir.display()
-                    );
-                }
-                keep
-            })
-            .find_map(|search_dir| {
-                let dic = search_dir.join(lang).with_extension("dic");
-                if !dic.is_file() {
-                    debug!(
-                        "Dictionary path dervied from search dir is not a file {}",
-                        dic.display()
-                    );
-                    return None;
-                }
-                let aff = search_dir.join(lang).with_extension("aff");
-                if !aff.is_file() {
-                    debug!(
-                        "Affixes path dervied from search dir is not a file {}",
-                        aff.display()
-                    );
-                    return None;
-                }
-                debug!("Using dic {} and aff {}", dic.display(), aff.display());
-                Some((dic, aff))
-            })
-            .ok_or_else(|| {
-                anyhow!("Failed to find any {lang}.dic / {lang}.aff in any search dir or no search provided",
-                lang = lang)
-            })?;
+        let (dic, aff): (PathBuf, PathBuf) = if let Some((dic, aff)) = find_dictionary(config) {
+            info!("Using dic {} and aff {}", dic.display(), aff.display());
+            (dic, aff)
+        } else if embedded::provides(lang) {
+            // libhunspell only reads files
+            let (dic, aff) = embedded::to_files()?;
+            info!(
+                "Found no {lang}.dic / {lang}.aff in any search dir, using the embedded {lang} dictionary written to {dir}",
+                lang = lang,
+                dir = dic.parent().unwrap_or(&dic).display()
+            );
+            (dic, aff)
+        } else {
+            bail!(
+                "Failed to find any {lang}.dic / {lang}.aff in any search dir or no search provided, and no {lang} dictionary is embedded",
+                lang = lang
+            )
+        };
 
         let dic = dic.to_str().unwrap();
         let aff = aff.to_str().unwrap();
@@ -108,143 +78,63 @@ impl HunspellChecker {
                 )
             }
         }
+
+        for extra_wordlist in config.extra_wordlists().iter() {
+            trace!("Adding extra word list {}", extra_wordlist.display());
+            for word in wordlist::load(extra_wordlist)? {
+                if !hunspell.add(word.as_str()) {
+                    bail!(
+                        "Failed to add word {} of extra word list {}",
+                        word,
+                        extra_wordlist.display()
+                    )
+                }
+            }
+        }
+
+        if let Some(project_wordlist) = config.project_wordlist() {
+            trace!("Adding project word list {}", project_wordlist.display());
+            for word in wordlist::load(project_wordlist)? {
+                if !hunspell.add(word.as_str()) {
+                    warn!("Failed to add word {} of the project word list", word);
+                }
+            }
+        }
         Ok(hunspell)
     }
 }
 
 impl Checker for HunspellChecker {
     type Config = crate::config::HunspellConfig;
-    fn check<'a, 's>(docu: &'a Documentation, config: &Self::Config) -> Result<SuggestionSet<'s>>
-    where
-        'a: 's,
-    {
-        let hunspell = Self::inner_init(config)?;
-
-        let (transform_regex, allow_concatenated, allow_dashed) =
-            if let Some(quirks) = &config.quirks {
-                (
-                    quirks.transform_regex(),
-                    quirks.allow_concatenated(),
-                    quirks.allow_dashed(),
-                )
-            } else {
-                (&[][..], false, false)
-            };
-
-        let suggestions = docu.iter().try_fold::<SuggestionSet, _, Result<_>>(
-            SuggestionSet::new(),
-            move |mut acc, (origin, chunks)| {
-                debug!("Processing {}", origin.as_path().display());
-
-                for chunk in chunks {
-                    let plain = chunk.erase_markdown();
-                    trace!("{:?}", &plain);
-                    let txt = plain.as_str();
-                    for range in tokenize(txt) {
-                        let word = sub_chars(txt, range.clone());
-                        if transform_regex.is_empty() {
-                            obtain_suggestions(
-                                &plain,
-                                chunk,
-                                &hunspell,
-                                origin,
-                                word,
-                                range,
-                                allow_concatenated,
-                                allow_dashed,
-                                &mut acc,
-                            )
-                        } else {
-                            match transform(&transform_regex[..], word.as_str(), range.clone()) {
-                                Transformed::Fragments(word_fragments) => {
-                                    for (range, word_fragment) in word_fragments {
-                                        obtain_suggestions(
-                                            &plain,
-                                            chunk,
-                                            &hunspell,
-                                            origin,
-                                            word_fragment.to_owned(),
-                                            range,
-                                            allow_concatenated,
-                                            allow_dashed,
-                                            &mut acc,
-                                        );
-                                    }
-                                }
-                                Transformed::Atomic((range, word)) => {
-                                    obtain_suggestions(
-                                        &plain,
-                                        chunk,
-                                        &hunspell,
-                                        origin,
-                                        word.to_owned(),
-                                        range,
-                                        allow_concatenated,
-                                        allow_dashed,
-                                        &mut acc,
-                                    );
-                                }
-                                Transformed::Whitelisted(_) => {}
-                            }
-                        }
-                    }
-                }
-                Ok(acc)
-            },
-        )?;
+    type Worker = Hunspell;
+
+    const DETECTOR: Detector = Detector::Hunspell;
 
-        // TODO sort spans by file and line + column
-        Ok(suggestions)
+    fn worker(config: &Self::Config) -> Result<Self::Worker> {
+        Self::inner_init(config)
     }
-}
 
-fn obtain_suggestions<'s>(
-    plain: &PlainOverlay,
-    chunk: &'s CheckableChunk,
-    hunspell: &Hunspell,
-    origin: &ContentOrigin,
-    word: String,
-    range: Range,
-    allow_concatenated: bool,
-    allow_dashed: bool,
-    acc: &mut SuggestionSet<'s>,
-) {
-    if !hunspell.check(&word) {
-        trace!("No match for word (plain range: {:?}): >{}<", &range, &word);
-        // get rid of single character suggestions
-        let replacements = hunspell
-            .suggest(&word)
-            .into_iter()
-            .filter(|x| x.len() > 1) // single char suggestions tend to be useless
-            .collect::<Vec<_>>();
-
-        if allow_concatenated && replacements_contain_dashless(&word, replacements.as_slice()) {
-            trace!(target: "quirks", "Found dashless word in replacement suggestions, treating {} as ok", &word);
-            return;
-        }
-        if allow_dashed && replacements_contain_dashed(&word, replacements.as_slice()) {
-            trace!(target: "quirks", "Found dashed word in replacement suggestions, treating {} as ok", &word);
-            return;
-        }
-        for (range, span) in plain.find_spans(range.clone()) {
-            acc.add(
-                origin.clone(),
-                Suggestion {
-                    detector: Detector::Hunspell,
-                    range,
-                    span,
-                    origin: origin.clone(),
-                    replacements: replacements.clone(),
-                    chunk,
-                    description: Some("Possible spelling mistake found.".to_owned()),
-                },
-            )
-        }
-    } else {
-        trace!(
-            "Found a match for word (plain range: {:?}): >{}<",
-            &range,
-            word
-        );
+    fn fingerprint_files(config: &Self::Config) -> Vec<PathBuf> {
+        dictionary_files(config)
+    }
+
+    fn check_chunks<'a, 's>(
+        hunspell: &mut Self::Worker,
+        origin: &ContentOrigin,
+        chunks: &[&'a CheckableChunk],
+        config: &Self::Config,
+    ) -> Result<Vec<Suggestion<'s>>>
+    where
+        'a: 's,
+    {
+        let hunspell = &*hunspell;
+        Ok(check_words(
+            Detector::Hunspell,
+            origin,
+            chunks,
+            config,
+            |word| hunspell.check(word),
+            |word| hunspell.suggest(word),
+        ))
     }
 }
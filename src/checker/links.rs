@@ -0,0 +1,663 @@
+//! Links within the documentation which lead nowhere
+//!
+//! All checks are offline:
+//!
+//! * intra-doc links of doc comments, i.e. ``[`Foo::bar`]``, are resolved against
+//!   the items of the crate, paths into other crates are not verified
+//! * relative links of markdown files must point to existing files, and their
+//!   `#anchors` to existing headings
+//! * reference-style links must have a matching link definition
+//!
+//! Relative links of doc comments are not checked, rustdoc resolves them relative
+//! to the generated html page rather than the source file.
+//!
+//! Replacements for a misspelled `#anchor` only cover the destination of the link,
+//! references to a link definition are reported without any.
+
+use super::symbols::{distance, KNOWN};
+use super::{pool, Checker, Detector, Sink, Suggestion};
+
+use crate::config::LinksConfig;
+use crate::documentation::{
+    collect_items, module_path, suppressed_ranges, CheckableChunk, ChunkKind, ContentOrigin,
+};
+use crate::traverse;
+use crate::{Documentation, Range, SuggestionSet};
+
+use anyhow::Result;
+use log::trace;
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Crates whose items can not be resolved offline.
+const EXTERNAL_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+/// Methods of common standard library traits, which are rarely implemented by hand.
+const TRAIT_METHODS: &[&str] = &[
+    "clone",
+    "default",
+    "fmt",
+    "from",
+    "into",
+    "try_from",
+    "try_into",
+    "from_str",
+    "eq",
+    "ne",
+    "cmp",
+    "partial_cmp",
+    "hash",
+    "deref",
+    "deref_mut",
+    "drop",
+    "next",
+    "into_iter",
+    "to_string",
+    "to_owned",
+    "as_ref",
+    "as_mut",
+    "borrow",
+    "borrow_mut",
+    "serialize",
+    "deserialize",
+];
+
+/// Upper bound of replacements offered per finding.
+const MAX_REPLACEMENTS: usize = 3;
+
+/// The items intra-doc links are resolved against.
+///
+/// Used as checker configuration, so the cache is invalidated whenever the items change.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct LinkIndex {
+    items: BTreeSet<String>,
+    symbols: BTreeSet<String>,
+}
+
+impl LinkIndex {
+    /// Collects the items of the whole crates the checked source files belong to,
+    /// links may point to items of modules which are not checked themselves.
+    pub(crate) fn new(docu: &Documentation, _config: &LinksConfig) -> Self {
+        let sources = docu
+            .iter()
+            .filter_map(|(origin, _)| match origin {
+                ContentOrigin::RustSourceFile(path) => Some(path),
+                _ => None,
+            })
+            .flat_map(|path| traverse::crate_sources(path))
+            .collect::<BTreeSet<_>>();
+        let mut crate_items = Documentation::new();
+        for path in sources {
+            match fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| collect_items(&content).ok())
+            {
+                Some(items) => crate_items.add_items(&module_path(&path), items),
+                None => trace!("Failed to collect the items of {}", path.display()),
+            }
+        }
+        Self {
+            items: docu.items().union(crate_items.items()).cloned().collect(),
+            symbols: docu
+                .symbols()
+                .union(crate_items.symbols())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Whether an item with `path`, or a path ending with it, exists.
+    fn contains(&self, path: &[&str]) -> bool {
+        let path = path.join("::");
+        let suffix = format!("::{}", path);
+        self.items
+            .iter()
+            .any(|item| *item == path || item.ends_with(&suffix))
+    }
+
+    /// Whether the intra-doc link `path` can be resolved, or at least not be proven wrong.
+    fn resolves(&self, path: &str) -> bool {
+        let mut segments = path.split("::").collect::<Vec<_>>();
+        let relative = segments
+            .iter()
+            .take_while(|segment| matches!(**segment, "crate" | "self" | "super" | "Self"))
+            .count();
+        segments.drain(..relative);
+
+        let (first, last) = match (segments.first(), segments.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return true,
+        };
+        if segments.len() == 1 && KNOWN.contains(&first) {
+            return true;
+        }
+        if relative == 0
+            && segments.len() > 1
+            && (EXTERNAL_CRATES.contains(&first) || !self.symbols.contains(first))
+        {
+            // an item of another crate
+            return true;
+        }
+        if self.contains(&segments) {
+            return true;
+        }
+        // methods of derived or std traits are not declared within the crate
+        segments.len() > 1
+            && TRAIT_METHODS.contains(&last)
+            && self.contains(&segments[..segments.len() - 1])
+    }
+}
+
+/// Extract the path of an intra-doc link, i.e. `Foo::bar` of ``[`struct@Foo::bar()`]``.
+///
+/// Returns `None` if the link text is not a path at all.
+fn intra_doc_path(link: &str) -> Option<&str> {
+    let mut path = link.trim().trim_matches('`');
+    if let Some((disambiguator, rest)) = path.split_once('@') {
+        if !disambiguator.chars().all(|c| c.is_ascii_lowercase()) {
+            return None;
+        }
+        if matches!(disambiguator, "prim" | "primitive") {
+            return None;
+        }
+        path = rest;
+    }
+    path = path.trim_end_matches("()").trim_end_matches('!');
+    if let Some(idx) = path.find('<') {
+        if path.ends_with('>') {
+            path = &path[..idx];
+        }
+    }
+    let valid = !path.is_empty()
+        && path.split("::").all(|segment| {
+            segment
+                .chars()
+                .next()
+                .map(|c| c.is_alphabetic() || c == '_')
+                .unwrap_or(false)
+                && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+        });
+    if valid {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// The anchor of a heading as generated by GitHub and most other markdown renderers.
+fn slugify(heading: &str) -> String {
+    heading
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// All anchors of a markdown document, derived from the headings and explicit html anchors.
+fn anchors(content: &str) -> Vec<String> {
+    let mut acc = Vec::new();
+    let mut occurrences = HashMap::<String, usize>::new();
+    let mut heading = None::<String>;
+    for event in Parser::new_ext(content, Options::all()) {
+        match event {
+            Event::Start(Tag::Heading(_)) => heading = Some(String::new()),
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = heading.as_mut() {
+                    heading.push_str(&text);
+                }
+            }
+            Event::End(Tag::Heading(_)) => {
+                if let Some(heading) = heading.take() {
+                    let slug = slugify(&heading);
+                    let count = occurrences.entry(slug.clone()).or_insert(0);
+                    if *count == 0 {
+                        acc.push(slug);
+                    } else {
+                        acc.push(format!("{}-{}", slug, count));
+                    }
+                    *count += 1;
+                }
+            }
+            Event::Html(html) => {
+                for attribute in &["id=\"", "name=\""] {
+                    for (idx, _) in html.match_indices(attribute) {
+                        let value = &html[(idx + attribute.len())..];
+                        if let Some(end) = value.find('"') {
+                            acc.push(value[..end].to_owned());
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    acc
+}
+
+/// Decode `%20` and friends of a link destination.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' && idx + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[(idx + 1)..(idx + 3)]).unwrap_or_default();
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                idx += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[idx]);
+        idx += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// A link which leads nowhere, with the byte range of the link within the chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BrokenLink {
+    offset: Range,
+    description: String,
+    replacements: Vec<String>,
+}
+
+/// Check a relative link `dest` of the markdown file `path`, with the file `content`.
+fn check_file_link(path: &Path, content: &str, dest: &str) -> Option<(String, Vec<String>)> {
+    if dest.is_empty()
+        || dest.contains("://")
+        || dest.starts_with("//")
+        || dest.starts_with("mailto:")
+    {
+        return None;
+    }
+    let dest = dest.split('?').next().unwrap_or_default();
+    let (file, anchor) = match dest.split_once('#') {
+        Some((file, anchor)) => (file, Some(anchor)),
+        None => (dest, None),
+    };
+
+    let (target, target_content) = if file.is_empty() {
+        (path.to_owned(), content.to_owned())
+    } else {
+        let file = percent_decode(file);
+        let target = if let Some(file) = file.strip_prefix('/') {
+            // relative to the repository root, which is unknown, so use the closest match
+            path.ancestors()
+                .map(|dir| dir.join(file))
+                .find(|target| target.exists())
+                .unwrap_or_else(|| PathBuf::from(file))
+        } else {
+            path.parent().unwrap_or(path).join(&file)
+        };
+        if !target.exists() {
+            return Some((format!("Linked file `{}` does not exist.", file), vec![]));
+        }
+        let is_markdown = target.extension().map(|ext| ext == "md").unwrap_or(false);
+        match anchor {
+            Some(_) if is_markdown => {
+                let content = fs::read_to_string(&target).unwrap_or_default();
+                (target, content)
+            }
+            _ => return None,
+        }
+    };
+
+    let anchor = anchor.filter(|anchor| !anchor.is_empty())?;
+    let anchors = anchors(&target_content);
+    if anchors.iter().any(|known| known == anchor) {
+        return None;
+    }
+    let mut candidates = anchors
+        .into_iter()
+        .map(|known| (distance(anchor, &known), known))
+        .filter(|(distance, _known)| *distance <= 2.max(anchor.len() / 3))
+        .collect::<Vec<_>>();
+    candidates.sort();
+    let prefix = if file.is_empty() {
+        String::new()
+    } else {
+        file.to_owned()
+    };
+    Some((
+        format!(
+            "No heading with anchor `#{}` in `{}`.",
+            anchor,
+            target
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        ),
+        candidates
+            .into_iter()
+            .take(MAX_REPLACEMENTS)
+            .map(|(_distance, known)| format!("{}#{}", prefix, known))
+            .collect(),
+    ))
+}
+
+/// Byte range of the destination `dest` within the inline link at `offset` of `content`.
+///
+/// `None` for references to a link definition or escaped destinations.
+fn destination_range(content: &str, offset: &Range, dest: &str) -> Option<Range> {
+    let link = &content[offset.clone()];
+    let start = link.find("](")? + 2;
+    let start = offset.start + start + link[start..].find(dest)?;
+    Some(start..(start + dest.len()))
+}
+
+/// Find all broken links of `chunk`.
+fn broken_links(
+    origin: &ContentOrigin,
+    chunk: &CheckableChunk,
+    index: &LinkIndex,
+) -> Vec<BrokenLink> {
+    let content = chunk.as_str();
+    let (doc_comment, markdown_file) = match origin {
+        // regular comments and string literals are not rendered, so there are no links to follow
+        ContentOrigin::RustSourceFile(_) if chunk.kind() != ChunkKind::Documentation => {
+            return Vec::new()
+        }
+        ContentOrigin::RustSourceFile(_) => (true, None),
+        ContentOrigin::CommonMarkFile(path) => (false, Some(path.as_path())),
+        // neither rendered by rustdoc nor part of a markdown file
+        _ => return Vec::new(),
+    };
+
+    // unresolved references are reported with the reference as destination
+    let callback = |reference: &str, _: &str| Some((reference.to_owned(), String::new()));
+    let parser = Parser::new_with_broken_link_callback(content, Options::all(), Some(&callback));
+
+    let suppressed = suppressed_ranges(content);
+    let mut acc = Vec::new();
+    for (event, offset) in parser.into_offset_iter() {
+        let (link_type, dest) = match event {
+            Event::Start(Tag::Link(link_type, dest, _title)) => (link_type, dest),
+            _ => continue,
+        };
+        if suppressed.iter().any(|range| range.contains(&offset.start)) {
+            continue;
+        }
+        let broken = match link_type {
+            LinkType::ReferenceUnknown | LinkType::CollapsedUnknown | LinkType::ShortcutUnknown
+                if doc_comment =>
+            {
+                match intra_doc_path(&dest) {
+                    Some(path) if !index.resolves(path) => {
+                        Some((format!("Unresolved intra-doc link to `{}`.", path), vec![]))
+                    }
+                    Some(_) => None,
+                    None if link_type == LinkType::ShortcutUnknown => None,
+                    None => Some((format!("No link definition for `[{}]`.", dest), vec![])),
+                }
+            }
+            LinkType::ReferenceUnknown | LinkType::CollapsedUnknown => {
+                Some((format!("No link definition for `[{}]`.", dest), vec![]))
+            }
+            // plain text in brackets
+            LinkType::ShortcutUnknown | LinkType::Autolink | LinkType::Email => None,
+            _ if doc_comment => match intra_doc_path(&dest) {
+                Some(path) if !index.resolves(path) => {
+                    Some((format!("Unresolved intra-doc link to `{}`.", path), vec![]))
+                }
+                _ => None,
+            },
+            _ => markdown_file.and_then(|path| check_file_link(path, content, &dest)),
+        };
+        if let Some((description, mut replacements)) = broken {
+            // the replacements are destinations, so only the destination is replaced
+            let offset = if replacements.is_empty() {
+                offset
+            } else if let Some(range) = destination_range(content, &offset, &dest) {
+                range
+            } else {
+                replacements.clear();
+                offset
+            };
+            trace!("Broken link {:?}: {}", &offset, &description);
+            acc.push(BrokenLink {
+                offset,
+                description,
+                replacements,
+            });
+        }
+    }
+    acc
+}
+
+pub(crate) struct LinksChecker;
+
+impl Checker for LinksChecker {
+    type Config = LinkIndex;
+    type Worker = ();
+
+    const DETECTOR: Detector = Detector::Links;
+
+    fn worker(_config: &Self::Config) -> Result<Self::Worker> {
+        Ok(())
+    }
+
+    fn check_chunks<'a, 's>(
+        _worker: &mut Self::Worker,
+        origin: &ContentOrigin,
+        chunks: &[&'a CheckableChunk],
+        index: &Self::Config,
+    ) -> Result<Vec<Suggestion<'s>>>
+    where
+        'a: 's,
+    {
+        let mut acc = Vec::new();
+        for chunk in chunks.iter().copied() {
+            let content = chunk.as_str();
+            for broken in broken_links(origin, chunk, index) {
+                let start = content[..broken.offset.start].chars().count();
+                let end = start + content[broken.offset.clone()].chars().count();
+                // links spanning multiple lines are reported on the first one
+                if let Some((range, span)) = chunk.find_spans(start..end).into_iter().next() {
+                    acc.push(Suggestion {
+                        detector: Self::DETECTOR,
+                        range,
+                        span,
+                        origin: origin.clone(),
+                        replacements: broken.replacements,
+                        chunk,
+                        description: Some(broken.description),
+                    });
+                }
+            }
+        }
+        Ok(acc)
+    }
+
+    /// Never cached, the findings depend on the files of the repository.
+    fn check_with_sink<'a, 's>(
+        docu: &'a Documentation,
+        config: &Self::Config,
+        jobs: usize,
+        _cache_dir: Option<&Path>,
+        sink: &mut Sink<'_>,
+    ) -> Result<SuggestionSet<'s>>
+    where
+        'a: 's,
+    {
+        pool::check::<Self>(docu, config, jobs, None, sink)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LineColumn, Span};
+
+    #[test]
+    fn paths() {
+        assert_eq!(intra_doc_path("`Foo::bar`"), Some("Foo::bar"));
+        assert_eq!(intra_doc_path("struct@Foo"), Some("Foo"));
+        assert_eq!(intra_doc_path("`foo()`"), Some("foo"));
+        assert_eq!(intra_doc_path("vec!"), Some("vec"));
+        assert_eq!(intra_doc_path("Vec<T>"), Some("Vec"));
+        assert_eq!(intra_doc_path("prim@u8"), None);
+        assert_eq!(intra_doc_path("some words"), None);
+        assert_eq!(intra_doc_path("1"), None);
+    }
+
+    #[test]
+    fn slugs() {
+        assert_eq!(
+            slugify("Implemented Features + Roadmap"),
+            "implemented-features--roadmap"
+        );
+        assert_eq!(
+            anchors("# Title\n## Use `foo`\n# Title\n<a name=\"custom\"></a>\n"),
+            vec!["title", "use-foo", "title-1", "custom"]
+        );
+        assert_eq!(percent_decode("a%20b.md"), "a b.md");
+    }
+
+    #[test]
+    fn intra_doc_links() {
+        const SOURCE: &str = r#"
+use serde::Serialize;
+
+pub mod nested {
+    /// See [`Point::x`], [`crate::nested::Point`] and [`Point::clone`].
+    /// Not [`Point::z`] nor [`nested::Pointe`], but [`serde::Other`] and [`Vec`] are fine.
+    /// And [text][missing definition] is broken, [some text] is plain.
+    /// <!-- spellcheck:off -->
+    /// Suppressed [`Missng`].
+    /// <!-- spellcheck:on -->
+    #[derive(Clone)]
+    pub struct Point { pub x: f32 }
+}
+"#;
+        let origin = ContentOrigin::RustSourceFile(PathBuf::from("/repo/src/lib.rs"));
+        let docs = Documentation::from((origin, SOURCE));
+        let index = LinkIndex::new(&docs, &LinksConfig::default());
+        let suggestions = LinksChecker::check(&docs, &index, 1, None).unwrap();
+        let (_origin, suggestions) = suggestions.iter().next().expect("Must find broken links");
+        assert_eq!(
+            suggestions
+                .iter()
+                .map(|suggestion| suggestion.description.clone().unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                "Unresolved intra-doc link to `Point::z`.",
+                "Unresolved intra-doc link to `nested::Pointe`.",
+                "No link definition for `[missing definition]`.",
+            ]
+        );
+        assert_eq!(
+            suggestions[0].span,
+            Span {
+                start: LineColumn {
+                    line: 6,
+                    column: 12
+                },
+                end: LineColumn {
+                    line: 6,
+                    column: 23
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn crate_items() {
+        let dir =
+            std::env::temp_dir().join(format!("cargo-spellcheck-items-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]\nname = \"items\"\n").unwrap();
+        const LIB: &str =
+            "mod other;\n\n/// Returns a [`Widget`], not a [`Gadget`].\npub fn widget() {}\n";
+        fs::write(dir.join("src/lib.rs"), LIB).unwrap();
+        fs::write(dir.join("src/other.rs"), "pub struct Widget;\n").unwrap();
+
+        // only the crate root is checked
+        let origin = ContentOrigin::RustSourceFile(dir.join("src/lib.rs"));
+        let docs = Documentation::from((origin, LIB));
+        let index = LinkIndex::new(&docs, &LinksConfig::default());
+        let suggestions = LinksChecker::check(&docs, &index, 1, None).unwrap();
+        let (_origin, suggestions) = suggestions.iter().next().expect("Must find broken links");
+        assert_eq!(
+            suggestions
+                .iter()
+                .map(|suggestion| suggestion.description.clone().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["Unresolved intra-doc link to `Gadget`."]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn markdown_links() {
+        let dir =
+            std::env::temp_dir().join(format!("cargo-spellcheck-links-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(dir.join("docs/guide.md"), "# Getting Started\n").unwrap();
+
+        const README: &str = r#"# Readme
+
+[guide](docs/guide.md#getting-started), [missing](docs/missing.md),
+[typo](docs/guide.md#getting-startd), [top](#readme), [web](https://example.com),
+[broken][nowhere], [fine][def] and [plain], [defined typo][anchor].
+
+[def]: docs/guide.md
+[anchor]: docs/guide.md#getting-startd
+"#;
+        let path = dir.join("README.md");
+        fs::write(&path, README).unwrap();
+        let origin = ContentOrigin::CommonMarkFile(path);
+        let chunk = CheckableChunk::from_str(
+            README,
+            indexmap::indexmap! { 0..README.chars().count() => Span {
+                start: LineColumn { line: 1, column: 0 },
+                end: LineColumn { line: 9, column: 38 },
+            }},
+        );
+        let index = LinkIndex::new(&Documentation::new(), &LinksConfig::default());
+        let broken = broken_links(&origin, &chunk, &index);
+        assert_eq!(
+            broken
+                .iter()
+                .map(|broken| (
+                    &README[broken.offset.clone()],
+                    broken.description.as_str(),
+                    broken.replacements.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "[missing](docs/missing.md)",
+                    "Linked file `docs/missing.md` does not exist.",
+                    vec![]
+                ),
+                (
+                    "docs/guide.md#getting-startd",
+                    "No heading with anchor `#getting-startd` in `guide.md`.",
+                    vec!["docs/guide.md#getting-started".to_owned()]
+                ),
+                (
+                    "[broken][nowhere]",
+                    "No link definition for `[nowhere]`.",
+                    vec![]
+                ),
+                (
+                    "[defined typo][anchor]",
+                    "No heading with anchor `#getting-startd` in `guide.md`.",
+                    vec![]
+                ),
+            ]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -4,27 +4,120 @@
 //! Contains also helpers to avoid re-implementing generic
 //! algorithms again and again, i.e. tokenization.
 
+use crate::config::HunspellConfig;
+use crate::documentation::{CheckableChunk, ContentOrigin};
+use crate::util::sub_chars;
 use crate::{Config, Detector, Documentation, Suggestion, SuggestionSet};
 
 use anyhow::Result;
+use serde::Serialize;
 
 use crate::Range;
-use log::debug;
+use log::{debug, trace, warn};
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "hunspell")]
 mod hunspell;
 #[cfg(feature = "languagetool")]
 mod languagetool;
 
-#[cfg(any(feature = "languagetool", feature = "hunspell"))]
 mod quirks;
 
+use self::quirks::{
+    replacements_contain_dashed, replacements_contain_dashless, transform, Transformed,
+};
+
+pub mod cache;
+mod command;
+mod embedded;
+mod links;
+mod native;
+mod pool;
+mod symbols;
+pub(crate) mod wordlist;
+
+/// Receives the findings of one origin as soon as a checker is done with it.
+///
+/// Only called from the thread the check was started on, origins without findings are skipped.
+pub type Sink<'x> = dyn FnMut(&ContentOrigin, &[Suggestion<'_>]) -> Result<()> + 'x;
+
 /// Implementation for a checker
-pub(crate) trait Checker {
-    type Config;
-    fn check<'a, 's>(docu: &'a Documentation, config: &Self::Config) -> Result<SuggestionSet<'s>>
+pub trait Checker: Sized {
+    type Config: Serialize + Sync;
+    /// State owned by a single worker thread, i.e. a loaded dictionary.
+    type Worker;
+
+    /// The detector all suggestions of this checker are attributed to.
+    const DETECTOR: Detector;
+
+    /// Upper bound of concurrent workers, regardless of `--jobs`.
+    const MAX_JOBS: usize = usize::MAX;
+
+    /// Prepare the state of one worker.
+    fn worker(config: &Self::Config) -> Result<Self::Worker>;
+
+    /// Files besides the configuration itself, which affect the results,
+    /// i.e. dictionaries. Changing any of them invalidates the cache.
+    fn fingerprint_files(_config: &Self::Config) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Check the given chunks of a single origin.
+    fn check_chunks<'a, 's>(
+        worker: &mut Self::Worker,
+        origin: &ContentOrigin,
+        chunks: &[&'a CheckableChunk],
+        config: &Self::Config,
+    ) -> Result<Vec<Suggestion<'s>>>
     where
         'a: 's;
+
+    /// Check the whole documentation, spread over up to `jobs` workers.
+    ///
+    /// See `check_with_sink`.
+    fn check<'a, 's>(
+        docu: &'a Documentation,
+        config: &Self::Config,
+        jobs: usize,
+        cache_dir: Option<&Path>,
+    ) -> Result<SuggestionSet<'s>>
+    where
+        'a: 's,
+    {
+        Self::check_with_sink(docu, config, jobs, cache_dir, &mut |_, _| Ok(()))
+    }
+
+    /// Check the whole documentation, spread over up to `jobs` workers, and pass
+    /// the findings of each origin to `sink` as soon as it is checked.
+    ///
+    /// With a `cache_dir`, chunks which were checked before are not checked again.
+    /// Entries are keyed by the origin and content of a chunk, the configuration and
+    /// the `fingerprint_files`. Checkers with findings depending on anything else
+    /// must override this and pass no cache.
+    fn check_with_sink<'a, 's>(
+        docu: &'a Documentation,
+        config: &Self::Config,
+        jobs: usize,
+        cache_dir: Option<&Path>,
+        sink: &mut Sink<'_>,
+    ) -> Result<SuggestionSet<'s>>
+    where
+        'a: 's,
+    {
+        let cache = if let Some(dir) = cache_dir {
+            let fingerprint = cache::fingerprint(config, &Self::fingerprint_files(config))?;
+            Some(cache::Cache::load(dir, Self::DETECTOR, fingerprint))
+        } else {
+            None
+        };
+        let suggestions = pool::check::<Self>(docu, config, jobs, cache.as_ref(), sink)?;
+        if let Some(cache) = cache {
+            if let Err(e) = cache.store() {
+                warn!("Failed to store the {} cache: {}", Self::DETECTOR, e);
+            }
+        }
+        Ok(suggestions)
+    }
 }
 
 /// Returns absolute offsets and the data with the token in question.
@@ -74,12 +167,185 @@ fn tokenize(s: &str) -> Vec<Range> {
     bananasplit
 }
 
+/// Find the `<lang>.dic` and `<lang>.aff` file in the search dirs of a dictionary based checker.
+///
+/// Lookup paths are really just an attempt to provide a dictionary, so missing
+/// ones are skipped silently.
+fn find_dictionary(config: &HunspellConfig) -> Option<(PathBuf, PathBuf)> {
+    let lang = config.lang();
+    config
+        .search_dirs()
+        .iter()
+        .filter(|search_dir| {
+            let keep = search_dir.is_dir();
+            if !keep {
+                // search_dir also contains the default paths, so just silently ignore these
+                debug!(
+                    "Dictionary search path is not a directory {}",
+                    search_dir.display()
+                );
+            } else {
+                debug!("Found dictionary search path {}", search_dir.display());
+            }
+            keep
+        })
+        .find_map(|search_dir| {
+            let dic = search_dir.join(lang).with_extension("dic");
+            if !dic.is_file() {
+                debug!(
+                    "Dictionary path dervied from search dir is not a file {}",
+                    dic.display()
+                );
+                return None;
+            }
+            let aff = search_dir.join(lang).with_extension("aff");
+            if !aff.is_file() {
+                debug!(
+                    "Affixes path dervied from search dir is not a file {}",
+                    aff.display()
+                );
+                return None;
+            }
+            Some((dic, aff))
+        })
+}
+
+/// All files of a dictionary based checker which affect its results.
+fn dictionary_files(config: &HunspellConfig) -> Vec<PathBuf> {
+    let lang = config.lang();
+    let mut files = config
+        .search_dirs()
+        .iter()
+        .flat_map(|search_dir| {
+            vec![
+                search_dir.join(lang).with_extension("dic"),
+                search_dir.join(lang).with_extension("aff"),
+            ]
+        })
+        .collect::<Vec<_>>();
+    files.extend(config.extra_dictonaries().iter().cloned());
+    files.extend(config.extra_wordlists().iter().cloned());
+    files.extend(config.project_wordlist().map(|path| path.to_owned()));
+    files
+}
+
+/// Check every token of the `chunks` with a dictionary based checker, considering the quirks.
+///
+/// `check` tells if a word is spelled correctly, `suggest` provides replacements otherwise.
+fn check_words<'a, 's>(
+    detector: Detector,
+    origin: &ContentOrigin,
+    chunks: &[&'a CheckableChunk],
+    config: &HunspellConfig,
+    check: impl Fn(&str) -> bool,
+    suggest: impl Fn(&str) -> Vec<String>,
+) -> Vec<Suggestion<'s>>
+where
+    'a: 's,
+{
+    let (transform_regex, allow_concatenated, allow_dashed) = if let Some(quirks) = &config.quirks {
+        (
+            quirks.transform_regex(),
+            quirks.allow_concatenated(),
+            quirks.allow_dashed(),
+        )
+    } else {
+        (&[][..], false, false)
+    };
+
+    // the replacements of a misspelled word, `None` if it is correct or accepted by the quirks
+    let replacements = |word: &str| -> Option<Vec<String>> {
+        if check(word) {
+            trace!("Found a match for word >{}<", word);
+            return None;
+        }
+        let replacements = suggest(word)
+            .into_iter()
+            .filter(|x| x.len() > 1) // single char suggestions tend to be useless
+            .collect::<Vec<_>>();
+        if allow_concatenated && replacements_contain_dashless(word, replacements.as_slice()) {
+            trace!(target: "quirks", "Found dashless word in replacement suggestions, treating {} as ok", word);
+            return None;
+        }
+        if allow_dashed && replacements_contain_dashed(word, replacements.as_slice()) {
+            trace!(target: "quirks", "Found dashed word in replacement suggestions, treating {} as ok", word);
+            return None;
+        }
+        Some(replacements)
+    };
+
+    debug!("Processing {}", origin.as_path().display());
+
+    let mut acc = Vec::with_capacity(32);
+    for &chunk in chunks {
+        let plain = chunk.erase_markdown();
+        trace!("{:?}", &plain);
+        let txt = plain.as_str();
+        for range in tokenize(txt) {
+            let word = sub_chars(txt, range.clone());
+            let words = if transform_regex.is_empty() {
+                vec![(range, word)]
+            } else {
+                match transform(transform_regex, word.as_str(), range.clone()) {
+                    Transformed::Fragments(word_fragments) => word_fragments
+                        .into_iter()
+                        .map(|(range, word_fragment)| (range, word_fragment.to_owned()))
+                        .collect(),
+                    Transformed::Atomic((range, word)) => vec![(range, word.to_owned())],
+                    Transformed::Whitelisted(_) => Vec::new(),
+                }
+            };
+            for (range, word) in words {
+                if let Some(replacements) = replacements(&word) {
+                    trace!("No match for word (plain range: {:?}): >{}<", &range, &word);
+                    for (range, span) in plain.find_spans(range) {
+                        acc.push(Suggestion {
+                            detector,
+                            range,
+                            span,
+                            origin: origin.clone(),
+                            replacements: replacements.clone(),
+                            chunk,
+                            description: Some("Possible spelling mistake found.".to_owned()),
+                        })
+                    }
+                }
+            }
+        }
+    }
+    acc
+}
+
 /// Check a full document for violations using the tools we have.
-pub fn check<'a, 's>(documentation: &'a Documentation, config: &Config) -> Result<SuggestionSet<'s>>
+///
+/// The files are spread over up to `jobs` worker threads per checker,
+/// results of previous runs are reused from the `cache_dir` if given.
+pub fn check<'a, 's>(
+    documentation: &'a Documentation,
+    config: &Config,
+    jobs: usize,
+    cache_dir: Option<&Path>,
+) -> Result<SuggestionSet<'s>>
+where
+    'a: 's,
+{
+    check_with_sink(documentation, config, jobs, cache_dir, &mut |_, _| Ok(()))
+}
+
+/// Same as `check`, but the findings are passed to `sink` as soon as
+/// a checker is done with an origin, one checker after another.
+pub fn check_with_sink<'a, 's>(
+    documentation: &'a Documentation,
+    config: &Config,
+    jobs: usize,
+    cache_dir: Option<&Path>,
+    sink: &mut Sink<'_>,
+) -> Result<SuggestionSet<'s>>
 where
     'a: 's,
 {
     let mut collective = SuggestionSet::<'s>::new();
+    let mut active = false;
 
     #[cfg(feature = "languagetool")]
     {
@@ -90,9 +356,15 @@ where
                 .as_ref()
                 .expect("Must be Some(LanguageToolConfig) if is_enabled returns true");
 
-            let mut suggestions =
-                self::languagetool::LanguageToolChecker::check(documentation, config)?;
+            let mut suggestions = self::languagetool::LanguageToolChecker::check_with_sink(
+                documentation,
+                config,
+                jobs,
+                cache_dir,
+                sink,
+            )?;
             collective.join(suggestions);
+            active = true;
         }
     }
 
@@ -104,11 +376,113 @@ where
                 .hunspell
                 .as_ref()
                 .expect("Must be Some(HunspellConfig) if is_enabled returns true");
-            let suggestions = self::hunspell::HunspellChecker::check(documentation, config)?;
+            let suggestions = self::hunspell::HunspellChecker::check_with_sink(
+                documentation,
+                config,
+                jobs,
+                cache_dir,
+                sink,
+            )?;
+            collective.join(suggestions);
+            active = true;
+        }
+    }
+
+    // without `libhunspell`, the same dictionaries are looked up natively
+    #[cfg(not(feature = "hunspell"))]
+    {
+        if config.is_enabled(Detector::Hunspell) && !config.is_enabled(Detector::Native) {
+            debug!("Running native dictionary checks in place of Hunspell");
+            let config = config
+                .hunspell
+                .as_ref()
+                .expect("Must be Some(HunspellConfig) if is_enabled returns true");
+            let suggestions = self::native::NativeChecker::check_with_sink(
+                documentation,
+                config,
+                jobs,
+                cache_dir,
+                sink,
+            )?;
             collective.join(suggestions);
+            active = true;
         }
     }
 
+    if config.is_enabled(Detector::Native) {
+        debug!("Running native dictionary checks");
+        let config = config
+            .native
+            .as_ref()
+            .expect("Must be Some(HunspellConfig) if is_enabled returns true");
+        let suggestions = self::native::NativeChecker::check_with_sink(
+            documentation,
+            config,
+            jobs,
+            cache_dir,
+            sink,
+        )?;
+        collective.join(suggestions);
+        active = true;
+    }
+
+    if config.is_enabled(Detector::Symbols) {
+        debug!("Running symbol checks");
+        let config = config
+            .symbols
+            .as_ref()
+            .expect("Must be Some(SymbolsConfig) if is_enabled returns true");
+        let index = self::symbols::SymbolIndex::new(documentation.symbols().clone(), config);
+        let suggestions = self::symbols::SymbolsChecker::check_with_sink(
+            documentation,
+            &index,
+            jobs,
+            cache_dir,
+            sink,
+        )?;
+        collective.join(suggestions);
+        active = true;
+    }
+
+    if config.is_enabled(Detector::Links) {
+        debug!("Running link checks");
+        let config = config
+            .links
+            .as_ref()
+            .expect("Must be Some(LinksConfig) if is_enabled returns true");
+        let index = self::links::LinkIndex::new(documentation, config);
+        let suggestions = self::links::LinksChecker::check_with_sink(
+            documentation,
+            &index,
+            jobs,
+            cache_dir,
+            sink,
+        )?;
+        collective.join(suggestions);
+        active = true;
+    }
+
+    if config.is_enabled(Detector::Command) {
+        debug!("Running the external command");
+        let config = config
+            .command
+            .as_ref()
+            .expect("Must be Some(CommandConfig) if is_enabled returns true");
+        let suggestions = self::command::CommandChecker::check_with_sink(
+            documentation,
+            config,
+            jobs,
+            cache_dir,
+            sink,
+        )?;
+        collective.join(suggestions);
+        active = true;
+    }
+
+    if !active {
+        warn!("No checker is active, nothing was checked");
+    }
+
     Ok(collective)
 }
 
@@ -154,7 +528,7 @@ pub mod tests {
             content,
         ));
         let suggestion_set =
-            dummy::DummyChecker::check(&d, &()).expect("Dummy extraction must never fail");
+            dummy::DummyChecker::check(&d, &(), 1, None).expect("Dummy extraction must never fail");
 
         // one file
         assert_eq!(suggestion_set.len(), 1);
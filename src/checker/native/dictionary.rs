@@ -0,0 +1,745 @@
+//! Hunspell compatible dictionaries
+//!
+//! Supports the subset of the `.aff` format spelling dictionaries commonly use,
+//! see `man 5 hunspell`:
+//!
+//! * `SET` with `UTF-8`, any other encoding is read as `ISO8859-1`
+//! * `FLAG` with `long`, `num` and `UTF-8` flags, as well as `AF` flag aliases
+//! * `PFX` and `SFX` rules, including the cross product of both
+//! * `NEEDAFFIX`, `FORBIDDENWORD`, `KEEPCASE`, `NOSUGGEST` and `ONLYINCOMPOUND`
+//! * `TRY` and `REP` for suggestions, `IGNORE`
+//!
+//! Compounding rules are not supported, words containing digits are always accepted.
+
+use anyhow::{anyhow, Result};
+use log::debug;
+use std::collections::{HashMap, HashSet};
+
+type Flag = u32;
+
+/// Upper bound of suggestions per word.
+const MAX_SUGGESTIONS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlagMode {
+    /// A single ASCII char per flag, the default.
+    Short,
+    /// Two ASCII chars per flag.
+    Long,
+    /// Comma separated decimal numbers.
+    Num,
+    /// A single unicode char per flag.
+    Utf8,
+}
+
+impl FlagMode {
+    fn parse(self, s: &str) -> Vec<Flag> {
+        match self {
+            Self::Short | Self::Utf8 => s.chars().map(|c| c as Flag).collect(),
+            Self::Long => s
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(2)
+                .map(|pair| pair.iter().fold(0, |acc, &c| (acc << 16) | c as Flag))
+                .collect(),
+            Self::Num => s
+                .split(',')
+                .filter_map(|flag| flag.trim().parse().ok())
+                .collect(),
+        }
+    }
+}
+
+/// A single char of an affix condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Any,
+    Is(char),
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl Condition {
+    /// Parse a condition like `[^aeiou]y`, the sole `.` matches everything.
+    fn parse(s: &str) -> Vec<Self> {
+        let mut acc = Vec::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            acc.push(match c {
+                '.' => Self::Any,
+                '[' => {
+                    let mut class = chars.by_ref().take_while(|&c| c != ']').peekable();
+                    if class.peek() == Some(&'^') {
+                        Self::NoneOf(class.skip(1).collect())
+                    } else {
+                        Self::OneOf(class.collect())
+                    }
+                }
+                c => Self::Is(c),
+            });
+        }
+        if acc.iter().all(|condition| *condition == Self::Any) {
+            acc.clear();
+        }
+        acc
+    }
+
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Is(expected) => c == *expected,
+            Self::OneOf(class) => class.contains(&c),
+            Self::NoneOf(class) => !class.contains(&c),
+        }
+    }
+}
+
+/// A prefix or suffix rule.
+#[derive(Debug, Clone)]
+struct Affix {
+    flag: Flag,
+    cross_product: bool,
+    /// Removed from the root before `add` is appended or prepended.
+    strip: String,
+    add: String,
+    /// Must match the start of the root for prefixes, the end for suffixes.
+    condition: Vec<Condition>,
+}
+
+impl Affix {
+    fn matches_prefix_of(&self, root: &str) -> bool {
+        root.chars().count() >= self.condition.len()
+            && self
+                .condition
+                .iter()
+                .zip(root.chars())
+                .all(|(condition, c)| condition.matches(c))
+    }
+
+    fn matches_suffix_of(&self, root: &str) -> bool {
+        root.chars().count() >= self.condition.len()
+            && self
+                .condition
+                .iter()
+                .rev()
+                .zip(root.chars().rev())
+                .all(|(condition, c)| condition.matches(c))
+    }
+}
+
+/// Affix rules, indexed by the text they add.
+#[derive(Debug, Default)]
+struct Affixes {
+    rules: Vec<Affix>,
+    by_add: HashMap<String, Vec<usize>>,
+    max_add_len: usize,
+    /// Declared per flag by the header of its rules.
+    cross_product: HashMap<Flag, bool>,
+}
+
+impl Affixes {
+    fn push(&mut self, affix: Affix) {
+        self.max_add_len = self.max_add_len.max(affix.add.chars().count());
+        self.by_add
+            .entry(affix.add.clone())
+            .or_default()
+            .push(self.rules.len());
+        self.rules.push(affix);
+    }
+
+    fn with_add<'a>(&'a self, add: &str) -> impl Iterator<Item = &'a Affix> + 'a {
+        self.by_add
+            .get(add)
+            .into_iter()
+            .flatten()
+            .map(move |&idx| &self.rules[idx])
+    }
+}
+
+/// Decode the content of a dictionary file.
+fn decode(bytes: &[u8], utf8: bool) -> String {
+    if utf8 {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        bytes.iter().map(|&b| b as char).collect()
+    }
+}
+
+/// Split a line of a `.dic` file into the word and its flags, a `\/` is part of the word.
+fn split_entry(line: &str) -> (String, &str) {
+    // morphological fields follow after whitespace
+    let entry = line.split(&['\t', ' '][..]).next().unwrap_or("");
+    let mut word = String::with_capacity(entry.len());
+    let mut chars = entry.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' if chars.peek().map(|&(_, c)| c) == Some('/') => {
+                word.push('/');
+                chars.next();
+            }
+            '/' => return (word, &entry[idx + 1..]),
+            c => word.push(c),
+        }
+    }
+    (word, "")
+}
+
+fn is_capitalized(word: &str) -> bool {
+    let mut chars = word.chars();
+    chars.next().map(char::is_uppercase).unwrap_or(false) && chars.all(|c| !c.is_uppercase())
+}
+
+fn is_all_caps(word: &str) -> bool {
+    word.chars().any(char::is_alphabetic) && word.chars().all(|c| !c.is_lowercase())
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// A dictionary with affix rules.
+#[derive(Debug, Default)]
+pub(crate) struct Dictionary {
+    /// All homonyms of a word with their flags.
+    words: HashMap<String, Vec<Vec<Flag>>>,
+    prefixes: Affixes,
+    suffixes: Affixes,
+    replacements: Vec<(String, String)>,
+    try_chars: Vec<char>,
+    ignore: Vec<char>,
+    flag_mode: Option<FlagMode>,
+    aliases: Vec<Vec<Flag>>,
+    utf8: bool,
+    need_affix: Option<Flag>,
+    forbidden: Option<Flag>,
+    keep_case: Option<Flag>,
+    no_suggest: Option<Flag>,
+    only_in_compound: Option<Flag>,
+}
+
+impl Dictionary {
+    /// Load the content of an `.aff` and a `.dic` file.
+    pub(crate) fn new(aff: &[u8], dic: &[u8]) -> Result<Self> {
+        let mut dictionary = Self::default();
+        dictionary.parse_aff(aff)?;
+        dictionary.add_dic(dic);
+        debug!(
+            "Loaded {} words, {} prefix and {} suffix rules",
+            dictionary.words.len(),
+            dictionary.prefixes.rules.len(),
+            dictionary.suffixes.rules.len()
+        );
+        Ok(dictionary)
+    }
+
+    fn flag_mode(&self) -> FlagMode {
+        self.flag_mode.unwrap_or(FlagMode::Short)
+    }
+
+    /// Parse the flags of a word or affix, which might refer to an `AF` alias.
+    fn parse_flags(&self, s: &str) -> Vec<Flag> {
+        if !self.aliases.is_empty() {
+            if let Ok(idx) = s.parse::<usize>() {
+                return self
+                    .aliases
+                    .get(idx.wrapping_sub(1))
+                    .cloned()
+                    .unwrap_or_default();
+            }
+        }
+        self.flag_mode().parse(s)
+    }
+
+    fn parse_flag(&self, s: &str) -> Option<Flag> {
+        self.flag_mode().parse(s).into_iter().next()
+    }
+
+    fn parse_aff(&mut self, aff: &[u8]) -> Result<()> {
+        // the encoding is declared within the file itself, which is ASCII up to that point
+        self.utf8 = decode(aff, false).lines().any(|line| {
+            let mut fields = line.split_whitespace();
+            fields.next() == Some("SET")
+                && fields
+                    .next()
+                    .map(|encoding| encoding.eq_ignore_ascii_case("UTF-8"))
+                    .unwrap_or(false)
+        });
+        let content = decode(aff, self.utf8);
+        for (lineno, line) in content.lines().enumerate() {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let invalid = || anyhow!("Invalid affix file line {}: `{}`", lineno + 1, line);
+            match fields.as_slice() {
+                ["FLAG", mode, ..] => {
+                    self.flag_mode = Some(match *mode {
+                        "long" => FlagMode::Long,
+                        "num" => FlagMode::Num,
+                        "UTF-8" => FlagMode::Utf8,
+                        _ => return Err(invalid()),
+                    })
+                }
+                ["AF", count] if count.parse::<usize>().is_ok() => {}
+                ["AF", flags, ..] => {
+                    let flags = self.flag_mode().parse(flags);
+                    self.aliases.push(flags);
+                }
+                ["TRY", chars, ..] => self.try_chars = chars.chars().collect(),
+                ["IGNORE", chars, ..] => self.ignore = chars.chars().collect(),
+                ["REP", count] if count.parse::<usize>().is_ok() => {}
+                ["REP", from, to, ..] => self
+                    .replacements
+                    .push((from.replace('_', " "), to.replace('_', " "))),
+                ["NEEDAFFIX", flag, ..] | ["PSEUDOROOT", flag, ..] => {
+                    self.need_affix = self.parse_flag(flag)
+                }
+                ["FORBIDDENWORD", flag, ..] => self.forbidden = self.parse_flag(flag),
+                ["KEEPCASE", flag, ..] => self.keep_case = self.parse_flag(flag),
+                ["NOSUGGEST", flag, ..] => self.no_suggest = self.parse_flag(flag),
+                ["ONLYINCOMPOUND", flag, ..] => self.only_in_compound = self.parse_flag(flag),
+                [kind @ "PFX", flag, cross_product, count]
+                | [kind @ "SFX", flag, cross_product, count]
+                    if count.parse::<usize>().is_ok() =>
+                {
+                    // the header of the rules, the cross product applies to all of them
+                    let flag = self.parse_flag(flag).ok_or_else(invalid)?;
+                    let affixes = if *kind == "PFX" {
+                        &mut self.prefixes
+                    } else {
+                        &mut self.suffixes
+                    };
+                    affixes.cross_product.insert(flag, *cross_product == "Y");
+                }
+                [kind @ "PFX", flag, strip, add, rest @ ..]
+                | [kind @ "SFX", flag, strip, add, rest @ ..] => {
+                    let flag = self.parse_flag(flag).ok_or_else(invalid)?;
+                    let affixes = if *kind == "PFX" {
+                        &mut self.prefixes
+                    } else {
+                        &mut self.suffixes
+                    };
+                    let cross_product = affixes.cross_product.get(&flag).copied().unwrap_or(false);
+                    let zero = |s: &str| {
+                        if s == "0" {
+                            String::new()
+                        } else {
+                            s.to_owned()
+                        }
+                    };
+                    // continuation classes of the affix itself are not supported
+                    let add = add.split('/').next().unwrap_or("");
+                    affixes.push(Affix {
+                        flag,
+                        cross_product,
+                        strip: zero(strip),
+                        add: zero(add),
+                        condition: Condition::parse(rest.first().copied().unwrap_or(".")),
+                    });
+                }
+                _ => {}
+            }
+        }
+        if self.try_chars.is_empty() {
+            self.try_chars = ('a'..='z').collect();
+        }
+        Ok(())
+    }
+
+    /// Add all words of a `.dic` file, using the affix rules of this dictionary.
+    pub(crate) fn add_dic(&mut self, dic: &[u8]) {
+        let content = decode(dic, self.utf8);
+        for (lineno, line) in content.lines().enumerate() {
+            let line = line.trim();
+            // the first line carries the approximate number of words
+            if line.is_empty() || (lineno == 0 && line.parse::<usize>().is_ok()) {
+                continue;
+            }
+            let (word, flags) = split_entry(line);
+            if word.is_empty() {
+                continue;
+            }
+            let flags = self.parse_flags(flags);
+            self.words.entry(word).or_default().push(flags);
+        }
+    }
+
+    /// Add a single word without any affixes.
+    pub(crate) fn add(&mut self, word: &str) {
+        self.words
+            .entry(word.to_owned())
+            .or_default()
+            .push(Vec::new());
+    }
+
+    fn has(flags: &[Flag], flag: Option<Flag>) -> bool {
+        flag.map(|flag| flags.contains(&flag)).unwrap_or(false)
+    }
+
+    fn is_forbidden(&self, word: &str) -> bool {
+        self.words
+            .get(word)
+            .map(|homonyms| {
+                homonyms
+                    .iter()
+                    .any(|flags| Self::has(flags, self.forbidden))
+            })
+            .unwrap_or(false)
+    }
+
+    /// The first homonym of `root` which can stand on its own or carries all of the `required` flags.
+    fn root(&self, root: &str, required: &[Flag]) -> Option<&[Flag]> {
+        self.words
+            .get(root)?
+            .iter()
+            .map(|flags| flags.as_slice())
+            .find(|flags| {
+                if Self::has(flags, self.forbidden) {
+                    false
+                } else if required.is_empty() {
+                    !Self::has(flags, self.need_affix) && !Self::has(flags, self.only_in_compound)
+                } else {
+                    required.iter().all(|flag| flags.contains(flag))
+                }
+            })
+    }
+
+    /// Words derived from `form` by removing suffixes, with the flags they must carry.
+    fn strip_suffixes<'a>(&'a self, form: &'a str) -> impl Iterator<Item = (String, &'a Affix)> {
+        let chars = form.chars().collect::<Vec<_>>();
+        (0..=self.suffixes.max_add_len.min(chars.len().saturating_sub(1))).flat_map(move |len| {
+            let (stem, add) = chars.split_at(chars.len() - len);
+            let stem = stem.iter().collect::<String>();
+            let add = add.iter().collect::<String>();
+            self.suffixes
+                .with_add(&add)
+                .filter_map(move |affix| {
+                    let root = format!("{}{}", stem, affix.strip);
+                    if root.is_empty() || !affix.matches_suffix_of(&root) {
+                        return None;
+                    }
+                    Some((root, affix))
+                })
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// Words derived from `form` by removing prefixes.
+    fn strip_prefixes<'a>(&'a self, form: &'a str) -> impl Iterator<Item = (String, &'a Affix)> {
+        let chars = form.chars().collect::<Vec<_>>();
+        (0..=self.prefixes.max_add_len.min(chars.len().saturating_sub(1))).flat_map(move |len| {
+            let (add, stem) = chars.split_at(len);
+            let stem = stem.iter().collect::<String>();
+            let add = add.iter().collect::<String>();
+            self.prefixes
+                .with_add(&add)
+                .filter_map(move |affix| {
+                    let root = format!("{}{}", affix.strip, stem);
+                    if root.is_empty() || !affix.matches_prefix_of(&root) {
+                        return None;
+                    }
+                    Some((root, affix))
+                })
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// The flags of the dictionary entry `form` is derived from.
+    fn lookup(&self, form: &str) -> Option<&[Flag]> {
+        if let Some(flags) = self.root(form, &[]) {
+            return Some(flags);
+        }
+        for (root, suffix) in self.strip_suffixes(form) {
+            let required = [suffix.flag];
+            if let Some(flags) = self.root(&root, &required) {
+                return Some(flags);
+            }
+        }
+        for (stem, prefix) in self.strip_prefixes(form) {
+            let required = [prefix.flag];
+            if let Some(flags) = self.root(&stem, &required) {
+                return Some(flags);
+            }
+            if !prefix.cross_product {
+                continue;
+            }
+            for (root, suffix) in self.strip_suffixes(&stem) {
+                if !suffix.cross_product {
+                    continue;
+                }
+                let required = [prefix.flag, suffix.flag];
+                if let Some(flags) = self.root(&root, &required) {
+                    return Some(flags);
+                }
+            }
+        }
+        None
+    }
+
+    /// The flags of the entry `word` is derived from, considering its capitalization.
+    fn find(&self, word: &str) -> Option<&[Flag]> {
+        if self.is_forbidden(word) {
+            return None;
+        }
+        if let Some(flags) = self.lookup(word) {
+            return Some(flags);
+        }
+        let mut variants = Vec::with_capacity(2);
+        if is_all_caps(word) && word.chars().count() > 1 {
+            variants.push(capitalize(word));
+            variants.push(word.to_lowercase());
+        } else if is_capitalized(word) {
+            variants.push(word.to_lowercase());
+        }
+        variants.into_iter().find_map(|variant| {
+            if self.is_forbidden(&variant) {
+                return None;
+            }
+            self.lookup(&variant)
+                .filter(|flags| !Self::has(flags, self.keep_case))
+        })
+    }
+
+    fn strip_ignored(&self, word: &str) -> String {
+        word.chars().filter(|c| !self.ignore.contains(c)).collect()
+    }
+
+    /// Whether the `word` is spelled correctly.
+    pub(crate) fn check(&self, word: &str) -> bool {
+        let word = self.strip_ignored(word);
+        if word.is_empty() || word.chars().any(|c| c.is_numeric()) {
+            return true;
+        }
+        self.find(&word).is_some()
+    }
+
+    /// Whether `candidate` is correct and may be suggested, it might consist of multiple words.
+    fn suggestable(&self, candidate: &str) -> bool {
+        candidate.split(' ').all(|word| {
+            self.find(word)
+                .map(|flags| !Self::has(flags, self.no_suggest))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Correctly spelled words similar to `word`, most likely ones first.
+    pub(crate) fn suggest(&self, word: &str) -> Vec<String> {
+        let word = self.strip_ignored(word);
+        let chars = word.chars().collect::<Vec<_>>();
+        let lowercase = word.to_lowercase();
+        let mut candidates = Vec::with_capacity(256);
+
+        // differently capitalized
+        candidates.push(lowercase.clone());
+        candidates.push(capitalize(&word));
+
+        // typical misspellings
+        for (from, to) in self.replacements.iter() {
+            for (idx, _) in word.match_indices(from.as_str()) {
+                candidates.push(format!(
+                    "{}{}{}",
+                    &word[..idx],
+                    to,
+                    &word[idx + from.len()..]
+                ));
+            }
+        }
+
+        let collect = |chars: &[char]| chars.iter().collect::<String>();
+        // swapped neighbours
+        for idx in 1..chars.len() {
+            let mut swapped = chars.clone();
+            swapped.swap(idx - 1, idx);
+            candidates.push(collect(&swapped));
+        }
+        // wrong chars
+        for idx in 0..chars.len() {
+            for &c in self.try_chars.iter() {
+                if chars[idx] != c {
+                    let mut replaced = chars.clone();
+                    replaced[idx] = c;
+                    candidates.push(collect(&replaced));
+                }
+            }
+        }
+        // superfluous chars
+        for idx in 0..chars.len() {
+            let mut removed = chars.clone();
+            removed.remove(idx);
+            candidates.push(collect(&removed));
+        }
+        // missing chars
+        for idx in 0..=chars.len() {
+            for &c in self.try_chars.iter() {
+                let mut inserted = chars.clone();
+                inserted.insert(idx, c);
+                candidates.push(collect(&inserted));
+            }
+        }
+        // missing space
+        for idx in 1..chars.len() {
+            candidates.push(format!(
+                "{} {}",
+                collect(&chars[..idx]),
+                collect(&chars[idx..])
+            ));
+        }
+
+        let all_caps = is_all_caps(&word) && chars.len() > 1;
+        let capitalized = is_capitalized(&word);
+        let mut seen = HashSet::with_capacity(candidates.len());
+        candidates
+            .into_iter()
+            .filter(|candidate| !candidate.is_empty() && *candidate != word)
+            .filter(|candidate| seen.insert(candidate.clone()))
+            .filter(|candidate| self.suggestable(candidate))
+            .map(|candidate| {
+                // keep the capitalization of the misspelled word
+                if all_caps {
+                    candidate.to_uppercase()
+                } else if capitalized && candidate.to_lowercase() == candidate {
+                    capitalize(&candidate)
+                } else {
+                    candidate
+                }
+            })
+            .fold(Vec::with_capacity(MAX_SUGGESTIONS), |mut acc, candidate| {
+                if acc.len() < MAX_SUGGESTIONS && !acc.contains(&candidate) {
+                    acc.push(candidate);
+                }
+                acc
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = r#"SET UTF-8
+TRY esianrtolcdugmphbyfvkwzESIANRTOLCDUGMPHBYFVKWZ'
+NOSUGGEST !
+FORBIDDENWORD %
+KEEPCASE K
+
+REP 2
+REP f ph
+REP ph f
+
+PFX A Y 1
+PFX A   0     re         .
+
+SFX D Y 4
+SFX D   0     d          e
+SFX D   y     ied        [^aeiou]y
+SFX D   0     ed         [^ey]
+SFX D   0     ed         [aeiou]y
+
+SFX S Y 3
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+SFX S   0     s          [^sxzhy]
+"#;
+
+    const DIC: &str = r#"8
+create/ADS
+cry/DS
+play/ADS
+telephone/S
+Paris
+OpenSSL/K
+damn/!
+creatd/%
+"#;
+
+    fn dictionary() -> Dictionary {
+        Dictionary::new(AFF.as_bytes(), DIC.as_bytes()).expect("Affixes are valid")
+    }
+
+    #[test]
+    fn conditions() {
+        let condition = Condition::parse("[^aeiou]y");
+        assert_eq!(
+            condition,
+            vec![
+                Condition::NoneOf(vec!['a', 'e', 'i', 'o', 'u']),
+                Condition::Is('y')
+            ]
+        );
+        assert!(Condition::parse(".").is_empty());
+    }
+
+    #[test]
+    fn flags() {
+        assert_eq!(FlagMode::Short.parse("AB"), vec![65, 66]);
+        assert_eq!(
+            FlagMode::Long.parse("AaBb"),
+            vec![(65 << 16) | 97, (66 << 16) | 98]
+        );
+        assert_eq!(FlagMode::Num.parse("101,7"), vec![101, 7]);
+        assert_eq!(
+            split_entry("and\\/or/AB\tpo:conj"),
+            ("and/or".to_owned(), "AB")
+        );
+    }
+
+    #[test]
+    fn affixes() {
+        let dictionary = dictionary();
+        for word in &[
+            "create",
+            "created",
+            "creates",
+            "recreate",
+            "recreated",
+            "cry",
+            "cried",
+            "cries",
+            "play",
+            "played",
+            "plays",
+            "replayed",
+            "telephones",
+        ] {
+            assert!(dictionary.check(word), "{} must be correct", word);
+        }
+        for word in &["creatd", "cryed", "telephoned", "recry", "plaied", "Creatd"] {
+            assert!(!dictionary.check(word), "{} must be wrong", word);
+        }
+    }
+
+    #[test]
+    fn capitalization() {
+        let dictionary = dictionary();
+        assert!(dictionary.check("Created"));
+        assert!(dictionary.check("CREATED"));
+        assert!(dictionary.check("Paris"));
+        assert!(dictionary.check("PARIS"));
+        assert!(!dictionary.check("paris"));
+        assert!(dictionary.check("OpenSSL"));
+        assert!(!dictionary.check("OPENSSL"));
+        assert!(dictionary.check("3rd"));
+    }
+
+    #[test]
+    fn suggestions() {
+        let dictionary = dictionary();
+        assert_eq!(dictionary.suggest("craete")[0], "create");
+        assert_eq!(dictionary.suggest("Teleffone"), Vec::<String>::new());
+        assert!(dictionary
+            .suggest("telefones")
+            .contains(&"telephones".to_owned()));
+        let suggestions = dictionary.suggest("Playd");
+        assert!(suggestions.contains(&"Played".to_owned()));
+        assert!(suggestions.iter().all(|s| s.starts_with("Play")));
+        assert_eq!(dictionary.suggest("CRYED")[0], "CRIED");
+        assert_eq!(dictionary.suggest("playcry"), vec!["play cry"]);
+        // never suggest words flagged with `NOSUGGEST`
+        assert!(dictionary.check("damn"));
+        assert!(!dictionary.suggest("darn").contains(&"damn".to_owned()));
+    }
+}
@@ -0,0 +1,182 @@
+//! A dictionary check with affixes, without any system dependencies
+//!
+//! Reads the same `.dic` and `.aff` files as the `Hunspell` checker, but
+//! looks up words in pure Rust, see `dictionary.rs` for the supported subset.
+//! If no dictionary is found in the search dirs, the embedded one is read from
+//! memory, given its language matches.
+
+use super::{
+    check_words, dictionary_files, embedded, find_dictionary, wordlist, Checker, Detector,
+    Suggestion,
+};
+
+use crate::config::HunspellConfig;
+use crate::documentation::{CheckableChunk, ContentOrigin};
+
+use anyhow::{anyhow, bail, Result};
+use log::{info, trace};
+use std::fs;
+use std::path::PathBuf;
+
+mod dictionary;
+
+use self::dictionary::Dictionary;
+
+pub(crate) struct NativeChecker;
+
+impl NativeChecker {
+    fn inner_init(config: &HunspellConfig) -> Result<Dictionary> {
+        let lang = config.lang();
+        let mut dictionary = if let Some((dic, aff)) = find_dictionary(config) {
+            let read = |path: &PathBuf| {
+                fs::read(path).map_err(|e| anyhow!("Failed to read {}", path.display()).context(e))
+            };
+            let dictionary = Dictionary::new(&read(&aff)?, &read(&dic)?)
+                .map_err(|e| e.context(anyhow!("Failed to load {}", aff.display())))?;
+            info!("Using dic {} and aff {}", dic.display(), aff.display());
+            dictionary
+        } else if embedded::provides(lang) {
+            info!(
+                "Found no {lang}.dic / {lang}.aff in any search dir, using the embedded {lang} dictionary",
+                lang = lang
+            );
+            Dictionary::new(&embedded::aff()?, &embedded::dic()?)?
+        } else {
+            bail!(
+                "Failed to find any {lang}.dic / {lang}.aff in any search dir and no {lang} dictionary is embedded",
+                lang = lang
+            )
+        };
+
+        // be more strict about the extra dictionaries, they have to exist
+        for extra_dic in config.extra_dictonaries().iter() {
+            trace!("Adding extra dictionary {}", extra_dic.display());
+            if !extra_dic.is_file() {
+                bail!("Extra dictionary {} is not a file", extra_dic.display())
+            }
+            let content = fs::read(extra_dic).map_err(|e| {
+                anyhow!("Failed to read extra dictionary {}", extra_dic.display()).context(e)
+            })?;
+            dictionary.add_dic(&content);
+        }
+
+        for extra_wordlist in config.extra_wordlists().iter() {
+            trace!("Adding extra word list {}", extra_wordlist.display());
+            for word in wordlist::load(extra_wordlist)? {
+                dictionary.add(&word);
+            }
+        }
+
+        if let Some(project_wordlist) = config.project_wordlist() {
+            trace!("Adding project word list {}", project_wordlist.display());
+            for word in wordlist::load(project_wordlist)? {
+                dictionary.add(&word);
+            }
+        }
+        Ok(dictionary)
+    }
+}
+
+impl Checker for NativeChecker {
+    type Config = HunspellConfig;
+    type Worker = Dictionary;
+
+    const DETECTOR: Detector = Detector::Native;
+
+    fn worker(config: &Self::Config) -> Result<Self::Worker> {
+        Self::inner_init(config)
+    }
+
+    fn fingerprint_files(config: &Self::Config) -> Vec<PathBuf> {
+        dictionary_files(config)
+    }
+
+    fn check_chunks<'a, 's>(
+        dictionary: &mut Self::Worker,
+        origin: &ContentOrigin,
+        chunks: &[&'a CheckableChunk],
+        config: &Self::Config,
+    ) -> Result<Vec<Suggestion<'s>>>
+    where
+        'a: 's,
+    {
+        let dictionary = &*dictionary;
+        Ok(check_words(
+            Detector::Native,
+            origin,
+            chunks,
+            config,
+            |word| dictionary.check(word),
+            |word| dictionary.suggest(word),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SearchDirs;
+    use crate::util::sub_chars;
+    use crate::Documentation;
+
+    #[test]
+    fn search_dirs() {
+        let dir =
+            std::env::temp_dir().join(format!("cargo-spellcheck-native-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("xx_YY.aff"), "SET UTF-8\nSFX S Y 1\nSFX S 0 s .\n").unwrap();
+        fs::write(dir.join("xx_YY.dic"), "2\nmistake/S\nfind/S\n").unwrap();
+        let wordlist = dir.join("wordlist.txt");
+        fs::write(&wordlist, "a\n").unwrap();
+
+        let config = HunspellConfig {
+            lang: Some("xx_YY".to_owned()),
+            search_dirs: SearchDirs(Some(vec![dir.clone()])),
+            extra_dictonaries: None,
+            extra_wordlists: Some(vec![wordlist]),
+            quirks: None,
+            project_wordlist: None,
+        };
+        const SOURCE: &str = "/// Finds a mistkes.\nstruct Foo;\n";
+        let origin = ContentOrigin::RustSourceFile(PathBuf::from("src/foo.rs"));
+        let docs = Documentation::from((origin, SOURCE));
+        let suggestions = NativeChecker::check(&docs, &config, 1, None).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let (_origin, suggestions) = suggestions.iter().next().expect("Must find the typo");
+        assert_eq!(suggestions.len(), 1);
+        let suggestion = &suggestions[0];
+        assert_eq!(suggestion.detector, Detector::Native);
+        assert_eq!(
+            sub_chars(suggestion.chunk.as_str(), suggestion.range.clone()),
+            "mistkes"
+        );
+        assert_eq!(suggestion.replacements, vec!["mistakes".to_owned()]);
+
+        let missing = HunspellConfig {
+            lang: Some("zz_ZZ".to_owned()),
+            ..config
+        };
+        assert!(NativeChecker::worker(&missing).is_err());
+    }
+
+    #[test]
+    fn embedded_fallback() {
+        let config = HunspellConfig {
+            lang: Some("en_US".to_owned()),
+            search_dirs: SearchDirs(Some(vec![PathBuf::from("/nonexistent")])),
+            extra_dictonaries: None,
+            extra_wordlists: None,
+            quirks: None,
+            project_wordlist: None,
+        };
+        let dictionary = NativeChecker::worker(&config).expect("en_US is embedded");
+        for word in &["iterator", "Documented", "doesn't", "color", "RECEIVE"] {
+            assert!(dictionary.check(word), "{} must be correct", word);
+        }
+        assert!(!dictionary.check("recieve"));
+        assert!(dictionary
+            .suggest("recieve")
+            .contains(&"receive".to_owned()));
+    }
+}
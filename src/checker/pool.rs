@@ -0,0 +1,220 @@
+//! Spread the checks of a whole documentation set over a pool of worker threads.
+//!
+//! Every worker owns its checker state, i.e. a loaded dictionary, and grabs the
+//! next unchecked origin until none are left. The results of each origin are passed
+//! to the sink as soon as they are available, and merged in the order of the
+//! documentation, so the returned set does not depend on the scheduling.
+
+use super::cache::Cache;
+use super::{Checker, Sink};
+use crate::documentation::{CheckableChunk, ContentOrigin};
+use crate::{Documentation, Suggestion, SuggestionSet};
+
+use anyhow::Result;
+use log::debug;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+/// Check the chunks of one origin, which are not covered by the `cache`.
+///
+/// The worker is only initialized once there is something to check.
+fn check_origin<'a, 's, C>(
+    worker: &mut Option<C::Worker>,
+    origin: &ContentOrigin,
+    chunks: &'a [CheckableChunk],
+    config: &C::Config,
+    cache: Option<&Cache>,
+) -> Result<Vec<Suggestion<'s>>>
+where
+    'a: 's,
+    C: Checker,
+{
+    let mut per_chunk = chunks
+        .iter()
+        .map(|chunk| cache.and_then(|cache| cache.lookup(origin, chunk)))
+        .collect::<Vec<_>>();
+    let unchecked = chunks
+        .iter()
+        .zip(per_chunk.iter())
+        .filter(|(_chunk, cached)| cached.is_none())
+        .map(|(chunk, _cached)| chunk)
+        .collect::<Vec<_>>();
+    if unchecked.is_empty() {
+        return Ok(per_chunk.into_iter().flatten().flatten().collect());
+    }
+
+    let worker = match worker {
+        Some(worker) => worker,
+        None => worker.insert(C::worker(config)?),
+    };
+    let mut fresh = C::check_chunks(worker, origin, unchecked.as_slice(), config)?;
+    for (chunk, slot) in chunks.iter().zip(per_chunk.iter_mut()) {
+        if slot.is_some() {
+            continue;
+        }
+        // retain the order of the suggestions within each chunk
+        let (own, others) = fresh
+            .into_iter()
+            .partition::<Vec<_>, _>(|suggestion| std::ptr::eq(suggestion.chunk, chunk));
+        fresh = others;
+        if let Some(cache) = cache {
+            cache.insert(origin, chunk, own.iter());
+        }
+        *slot = Some(own);
+    }
+    Ok(per_chunk.into_iter().flatten().flatten().collect())
+}
+
+/// Check all origins of `docu` with up to `jobs` concurrent workers.
+///
+/// The findings of each origin are passed to `sink` in the order the origins are done.
+#[cfg_attr(
+    not(any(feature = "hunspell", feature = "languagetool")),
+    allow(unused)
+)]
+pub(super) fn check<'a, 's, C>(
+    docu: &'a Documentation,
+    config: &C::Config,
+    jobs: usize,
+    cache: Option<&Cache>,
+    sink: &mut Sink<'_>,
+) -> Result<SuggestionSet<'s>>
+where
+    'a: 's,
+    C: Checker,
+{
+    let items = docu.iter().collect::<Vec<_>>();
+    let jobs = jobs.min(C::MAX_JOBS).min(items.len()).max(1);
+
+    let mut collective = SuggestionSet::new();
+    if jobs == 1 {
+        // not worth spawning a thread
+        let mut worker = None;
+        for (origin, chunks) in items {
+            let suggestions =
+                check_origin::<C>(&mut worker, origin, chunks.as_slice(), config, cache)?;
+            if !suggestions.is_empty() {
+                sink(origin, suggestions.as_slice())?;
+                collective.extend(origin.clone(), suggestions);
+            }
+        }
+        return Ok(collective);
+    }
+
+    debug!("Checking {} origins with {} workers", items.len(), jobs);
+
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let mut checked = Vec::with_capacity(items.len());
+    std::thread::scope(|scope| -> Result<()> {
+        let (sender, receiver) = mpsc::channel::<(usize, Vec<Suggestion<'s>>)>();
+        let handles = (0..jobs)
+            .map(|_| {
+                let sender = sender.clone();
+                let (items, next, failed) = (&items, &next, &failed);
+                scope.spawn(move || -> Result<()> {
+                    let mut worker = None;
+                    while !failed.load(Ordering::Relaxed) {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        let (origin, chunks) = match items.get(idx) {
+                            Some(item) => *item,
+                            None => break,
+                        };
+                        match check_origin::<C>(
+                            &mut worker,
+                            origin,
+                            chunks.as_slice(),
+                            config,
+                            cache,
+                        ) {
+                            Ok(suggestions) => {
+                                // the receiver only hangs up once the workers are done
+                                let _ = sender.send((idx, suggestions));
+                            }
+                            Err(e) => {
+                                failed.store(true, Ordering::Relaxed);
+                                return Err(e);
+                            }
+                        }
+                    }
+                    Ok(())
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(sender);
+
+        // hand out the findings while the workers are still busy
+        let mut streamed = Ok(());
+        for (idx, suggestions) in receiver {
+            if streamed.is_ok() && !suggestions.is_empty() {
+                streamed = sink(items[idx].0, suggestions.as_slice());
+                if streamed.is_err() {
+                    failed.store(true, Ordering::Relaxed);
+                }
+            }
+            checked.push((idx, suggestions));
+        }
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        streamed
+    })?;
+
+    checked.sort_by_key(|(idx, _)| *idx);
+    for (idx, suggestions) in checked {
+        if !suggestions.is_empty() {
+            collective.extend(items[idx].0.clone(), suggestions);
+        }
+    }
+    Ok(collective)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::dummy::DummyChecker;
+    use super::*;
+    use crate::ContentOrigin;
+    use std::path::PathBuf;
+
+    #[test]
+    fn deterministic_order() {
+        let mut docs = Documentation::new();
+        for i in 0..32 {
+            docs.join(Documentation::from((
+                ContentOrigin::RustSourceFile(PathBuf::from(format!("src/file{}.rs", i))),
+                "/// Some words\nstruct X;",
+            )));
+        }
+        let sequential = check::<DummyChecker>(&docs, &(), 1, None, &mut |_, _| Ok(())).unwrap();
+        let mut streamed = Vec::new();
+        let parallel = check::<DummyChecker>(&docs, &(), 4, None, &mut |origin, suggestions| {
+            streamed.push((origin.as_path().to_owned(), suggestions.len()));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(sequential.total_count(), 64);
+        assert_eq!(
+            sequential
+                .iter()
+                .map(|(origin, _)| origin)
+                .collect::<Vec<_>>(),
+            parallel
+                .iter()
+                .map(|(origin, _)| origin)
+                .collect::<Vec<_>>()
+        );
+        // every origin is streamed exactly once, regardless of the order
+        let mut collected = parallel
+            .iter()
+            .map(|(origin, suggestions)| (origin.as_path().to_owned(), suggestions.len()))
+            .collect::<Vec<_>>();
+        streamed.sort();
+        collected.sort();
+        assert_eq!(streamed, collected);
+    }
+}
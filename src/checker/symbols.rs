@@ -0,0 +1,323 @@
+//! Inline code referring to symbols which do not exist
+//!
+//! Inline code within the documentation mostly names items of the crate itself.
+//! An identifier in inline code which matches no known symbol, yet is only a few
+//! edits away from one, is most likely a typo, i.e. `HashMpa` instead of `HashMap`.
+//! Identifiers which resemble no symbol at all are left alone, they usually refer
+//! to external items or are not Rust code in the first place.
+
+use super::{Checker, Detector, Suggestion};
+
+use crate::config::SymbolsConfig;
+use crate::documentation::{suppressed_ranges, CheckableChunk, ContentOrigin};
+use crate::Range;
+
+use anyhow::Result;
+use log::trace;
+use pulldown_cmark::{Event, Options, Parser};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+
+/// Keywords, primitives and the prelude, which are known without being declared.
+pub(super) const KNOWN: &[&str] = &[
+    "async",
+    "await",
+    "break",
+    "const",
+    "continue",
+    "crate",
+    "else",
+    "enum",
+    "extern",
+    "false",
+    "impl",
+    "loop",
+    "match",
+    "move",
+    "return",
+    "self",
+    "Self",
+    "static",
+    "struct",
+    "super",
+    "trait",
+    "true",
+    "type",
+    "unsafe",
+    "where",
+    "while",
+    "bool",
+    "char",
+    "isize",
+    "usize",
+    "i128",
+    "u128",
+    "Some",
+    "None",
+    "Option",
+    "Result",
+    "String",
+    "Vec",
+    "Box",
+    "Clone",
+    "Copy",
+    "Default",
+    "Drop",
+    "Send",
+    "Sync",
+    "Sized",
+    "Iterator",
+    "IntoIterator",
+    "ToString",
+    "ToOwned",
+];
+
+/// Upper bound of replacements offered per finding.
+const MAX_REPLACEMENTS: usize = 3;
+
+/// The known symbols and the tolerated edit distance to them.
+///
+/// Used as checker configuration, so the cache is invalidated whenever the symbols change.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SymbolIndex {
+    symbols: BTreeSet<String>,
+    max_distance: usize,
+    /// The symbols in `normalize`d form.
+    #[serde(skip)]
+    normalized: HashSet<String>,
+}
+
+/// Lowercase and without underscores, so naming convention variants
+/// such as `end_column` and `endColumn` are equal.
+fn normalize(ident: &str) -> String {
+    ident.replace('_', "").to_lowercase()
+}
+
+impl SymbolIndex {
+    pub(crate) fn new(symbols: BTreeSet<String>, config: &SymbolsConfig) -> Self {
+        let normalized = symbols.iter().map(|symbol| normalize(symbol)).collect();
+        Self {
+            symbols,
+            max_distance: config.max_distance(),
+            normalized,
+        }
+    }
+
+    /// Whether `ident` names a known symbol, in any naming convention or as plural.
+    fn contains(&self, ident: &str) -> bool {
+        if KNOWN.contains(&ident) || self.symbols.contains(ident) {
+            return true;
+        }
+        let ident = normalize(ident);
+        self.normalized.contains(&ident)
+            || ident
+                .strip_suffix('s')
+                .map(|singular| self.normalized.contains(singular))
+                .unwrap_or(false)
+    }
+
+    /// The symbols closest to `ident`, if any is within the tolerated edit distance.
+    ///
+    /// Short identifiers tolerate fewer edits, otherwise nearly every
+    /// word would be within reach of some symbol.
+    fn closest(&self, ident: &str) -> Vec<String> {
+        let len = ident.chars().count();
+        let tolerance = self.max_distance.min(len.saturating_sub(1) / 4);
+        if tolerance == 0 {
+            return Vec::new();
+        }
+        let mut candidates = self
+            .symbols
+            .iter()
+            .filter(|symbol| {
+                let symbol_len = symbol.chars().count();
+                symbol_len.max(len) - symbol_len.min(len) <= tolerance
+            })
+            .map(|symbol| (distance(ident, symbol), symbol))
+            .filter(|(distance, _symbol)| *distance <= tolerance)
+            .collect::<Vec<_>>();
+        candidates.sort();
+        candidates
+            .into_iter()
+            .take(MAX_REPLACEMENTS)
+            .map(|(_distance, symbol)| symbol.clone())
+            .collect()
+    }
+}
+
+/// Edit distance of two strings, counting a transposition of adjacent characters as one edit.
+pub(super) fn distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    // rows for the prefixes of `a` of length `i - 2`, `i - 1` and `i`
+    let mut before = vec![0; b.len() + 1];
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// All identifiers within the inline code of `chunk`, with their char range within the chunk.
+///
+/// Inline code suppressed by spellcheck directives is skipped.
+fn inline_code_identifiers(chunk: &CheckableChunk) -> Vec<(Range, String)> {
+    let content = chunk.as_str();
+    let suppressed = suppressed_ranges(content);
+    let mut acc = Vec::new();
+    for (event, offset) in Parser::new_ext(content, Options::all()).into_offset_iter() {
+        let code = match event {
+            Event::Code(code) => code,
+            _ => continue,
+        };
+        if suppressed.iter().any(|range| range.contains(&offset.start)) {
+            continue;
+        }
+        // the offset includes the backticks
+        let start = match content[offset.clone()].find(code.as_ref()) {
+            Some(idx) => offset.start + idx,
+            None => continue,
+        };
+        let mut char_offset = content[..start].chars().count();
+        let mut ident = String::new();
+        for c in code.chars().chain(std::iter::once(' ')) {
+            if c.is_alphanumeric() || c == '_' {
+                ident.push(c);
+                char_offset += 1;
+                continue;
+            }
+            if !ident.is_empty() {
+                let len = ident.chars().count();
+                if !ident.starts_with(|c: char| c.is_numeric()) {
+                    acc.push(((char_offset - len)..char_offset, ident.clone()));
+                }
+                ident.clear();
+            }
+            char_offset += 1;
+        }
+    }
+    acc
+}
+
+pub(crate) struct SymbolsChecker;
+
+impl Checker for SymbolsChecker {
+    type Config = SymbolIndex;
+    type Worker = ();
+
+    const DETECTOR: Detector = Detector::Symbols;
+
+    fn worker(_config: &Self::Config) -> Result<Self::Worker> {
+        Ok(())
+    }
+
+    fn check_chunks<'a, 's>(
+        _worker: &mut Self::Worker,
+        origin: &ContentOrigin,
+        chunks: &[&'a CheckableChunk],
+        index: &Self::Config,
+    ) -> Result<Vec<Suggestion<'s>>>
+    where
+        'a: 's,
+    {
+        let mut acc = Vec::new();
+        for chunk in chunks.iter().copied() {
+            for (range, ident) in inline_code_identifiers(chunk) {
+                if chunk.allowed_words().contains(&ident) || index.contains(&ident) {
+                    continue;
+                }
+                let replacements = index.closest(&ident);
+                if replacements.is_empty() {
+                    continue;
+                }
+                trace!("Unknown symbol >{}< resembles {:?}", ident, replacements);
+                for (range, span) in chunk.find_spans(range) {
+                    acc.push(Suggestion {
+                        detector: Self::DETECTOR,
+                        range,
+                        span,
+                        origin: origin.clone(),
+                        replacements: replacements.clone(),
+                        chunk,
+                        description: Some(format!("No symbol named `{}` exists.", ident)),
+                    });
+                }
+            }
+        }
+        Ok(acc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Documentation;
+    use std::path::PathBuf;
+
+    #[test]
+    fn edit_distance() {
+        assert_eq!(distance("HashMap", "HashMap"), 0);
+        assert_eq!(distance("HashMpa", "HashMap"), 1);
+        assert_eq!(distance("HashMa", "HashMap"), 1);
+        assert_eq!(distance("HasMpa", "HashMap"), 2);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn misspelled_symbol() {
+        const SOURCE: &str = r#"
+use std::collections::HashMap;
+
+/// Wraps a `HashMpa`, see `Registry::lookup` and `Registyr::insert`.
+///
+/// Neither `tokio` nor `config.toml` are symbols, `lookups` and `LOOKUP` are known.
+///
+/// <!-- spellcheck:off -->
+/// Suppressed `HashMpa`.
+/// <!-- spellcheck:on -->
+pub struct Registry {
+    entries: HashMap<String, usize>,
+}
+
+impl Registry {
+    pub fn lookup(&self) {}
+}
+"#;
+        let origin = ContentOrigin::RustSourceFile(PathBuf::from("/tmp/registry.rs"));
+        let docs = Documentation::from((origin, SOURCE));
+        let index = SymbolIndex::new(docs.symbols().clone(), &SymbolsConfig::default());
+        let suggestions = SymbolsChecker::check(&docs, &index, 1, None).unwrap();
+        let (_origin, suggestions) = suggestions.iter().next().expect("Must find typos");
+        let found = suggestions
+            .iter()
+            .map(|suggestion| {
+                (
+                    crate::util::sub_chars(suggestion.chunk.as_str(), suggestion.range.clone()),
+                    suggestion.replacements.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                ("HashMpa".to_owned(), vec!["HashMap".to_owned()]),
+                ("Registyr".to_owned(), vec!["Registry".to_owned()]),
+            ]
+        );
+        assert_eq!(suggestions[0].span.start.line, 4);
+        assert_eq!(suggestions[0].span.start.column, 13);
+    }
+}
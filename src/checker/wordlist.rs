@@ -0,0 +1,155 @@
+//! Plain text word lists
+//!
+//! One word per line, which is used for the project specific word list the
+//! interactive `fix` mode appends to, as well as for glossaries which are
+//! simpler to maintain than hunspell `.dic` files.
+//!
+//! ```text
+//! # comments start with a `#`, empty lines are ignored
+//! cargo
+//! spellcheck # trailing comments are fine too
+//! # entries prefixed with `~` are accepted case-insensitively
+//! ~GitHub
+//! ```
+
+use anyhow::{anyhow, bail, Result};
+use log::debug;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// Parse the content of a word list, `path` is only used for error messages.
+///
+/// A case-insensitive entry yields the word as written and its lowercase variant,
+/// the dictionary lookup then accepts the capitalized and upper case variants as well.
+pub(crate) fn parse(content: &str, path: &Path) -> Result<Vec<String>> {
+    let mut words = Vec::with_capacity(content.len() / 8);
+    for (lineno, line) in content.lines().enumerate() {
+        let lineno = lineno + 1;
+        let entry = if let Some(idx) = line.find('#') {
+            &line[..idx]
+        } else {
+            line
+        }
+        .trim();
+        if entry.is_empty() {
+            continue;
+        }
+        let (word, case_insensitive) = if let Some(word) = entry.strip_prefix('~') {
+            (word, true)
+        } else {
+            (entry, false)
+        };
+        if word.is_empty() {
+            bail!(
+                "{}:{}: Case-insensitive entry without a word",
+                path.display(),
+                lineno
+            );
+        }
+        if word.contains(char::is_whitespace) {
+            bail!(
+                "{}:{}: Entry `{}` must be a single word",
+                path.display(),
+                lineno,
+                word
+            );
+        }
+        if word.contains('/') {
+            bail!(
+                "{}:{}: Entry `{}` contains a `/`, affix flags require a hunspell `.dic` file",
+                path.display(),
+                lineno,
+                word
+            );
+        }
+        words.push(word.to_owned());
+        if case_insensitive {
+            let lowercase = word.to_lowercase();
+            if lowercase != word {
+                words.push(lowercase);
+            }
+        }
+    }
+    Ok(words)
+}
+
+/// Load all words of the word list, a missing file is equivalent to an empty list.
+#[cfg_attr(not(feature = "hunspell"), allow(unused))]
+pub(crate) fn load(path: &Path) -> Result<Vec<String>> {
+    if !path.exists() {
+        debug!("Word list {} does not exist (yet)", path.display());
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read word list {}", path.display()).context(e))?;
+    parse(&content, path)
+}
+
+/// Append a single word to the word list, the file is created if it does not exist yet.
+pub(crate) fn append(path: &Path, word: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| anyhow!("Failed to create directories {}", dir.display()).context(e))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| anyhow!("Failed to open word list {}", path.display()).context(e))?;
+    writeln!(file, "{}", word.trim())
+        .map_err(|e| anyhow!("Failed to append to word list {}", path.display()).context(e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_and_load() {
+        let path = std::env::temp_dir()
+            .join(format!("cargo-spellcheck-{}", std::process::id()))
+            .join("wordlist.txt");
+        let _ = fs::remove_file(&path);
+
+        assert!(load(&path).unwrap().is_empty());
+        append(&path, "spellcheck").unwrap();
+        append(&path, " bandaid\n").unwrap();
+        assert_eq!(load(&path).unwrap(), vec!["spellcheck", "bandaid"]);
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn comments_and_case() {
+        const CONTENT: &str = r#"
+# glossary
+cargo
+spellcheck   # trailing comment
+
+~GitHub
+~crate
+"#;
+        assert_eq!(
+            parse(CONTENT, Path::new("glossary.txt")).unwrap(),
+            vec!["cargo", "spellcheck", "GitHub", "github", "crate"]
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        let path = Path::new("glossary.txt");
+        let err = parse("cargo\ntwo words\n", path).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "glossary.txt:2: Entry `two words` must be a single word"
+        );
+        let err = parse("# header\n\n~\n", path).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "glossary.txt:3: Case-insensitive entry without a word"
+        );
+        assert!(parse("word/S\n", path).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! Configure cargo-spellcheck
 //!
-//! Supports `Hunspell` and `LanguageTool` scopes.
+//! Supports `Hunspell`, `Native`, `LanguageTool`, `Symbols`, `Links` and `Command` scopes,
+//! as well as the `Reflow` settings of the `reflow` sub-command.
 //!
 //! A default configuration will be generated in the default
 //! location by default. Default. Default default default.
@@ -22,12 +23,30 @@ use std::path::{Path, PathBuf};
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
+    /// Also check regular `//` and `/* */` comments, not only doc comments.
+    #[serde(default)]
+    pub dev_comments: bool,
+    /// Also check the string literals of `panic!`, `format!` like and log macros
+    /// as well as of `.expect(..)` calls.
+    #[serde(default)]
+    pub string_literals: bool,
     #[serde(alias = "Hunspell")]
     pub hunspell: Option<HunspellConfig>,
     #[serde(alias = "LanguageTool")]
     #[serde(alias = "languageTool")]
     #[serde(alias = "Languagetool")]
     pub languagetool: Option<LanguageToolConfig>,
+    #[serde(alias = "Symbols")]
+    pub symbols: Option<SymbolsConfig>,
+    #[serde(alias = "Links")]
+    pub links: Option<LinksConfig>,
+    #[serde(alias = "Command")]
+    pub command: Option<CommandConfig>,
+    /// Same options as `Hunspell`, but looks words up without `libhunspell`.
+    #[serde(alias = "Native")]
+    pub native: Option<HunspellConfig>,
+    #[serde(alias = "Reflow")]
+    pub reflow: Option<ReflowConfig>,
 }
 
 #[derive(Debug)]
@@ -260,8 +279,13 @@ pub struct HunspellConfig {
     pub search_dirs: SearchDirs,
     /// Additional dictionaries for topic specific lingo.
     pub extra_dictonaries: Option<Vec<PathBuf>>,
+    /// Additional plain text word lists, one word per line, relative to the config file.
+    pub extra_wordlists: Option<Vec<PathBuf>>,
     /// Additional quirks besides dictionary lookups.
     pub quirks: Option<Quirks>,
+    /// Project specific word list, one word per line, which words are added to
+    /// from the interactive `fix` mode.
+    pub project_wordlist: Option<PathBuf>,
 }
 
 impl HunspellConfig {
@@ -289,7 +313,41 @@ impl HunspellConfig {
         }
     }
 
+    pub fn extra_wordlists(&self) -> &[PathBuf] {
+        if let Some(ref extra_wordlists) = self.extra_wordlists {
+            extra_wordlists.as_slice()
+        } else {
+            &[]
+        }
+    }
+
+    pub fn project_wordlist(&self) -> Option<&Path> {
+        self.project_wordlist.as_deref()
+    }
+
     pub fn sanitize_paths(&mut self, base: &Path) -> Result<()> {
+        // the word list does not have to exist yet, so it can not be canonicalized
+        if let Some(ref mut project_wordlist) = self.project_wordlist {
+            if !project_wordlist.is_absolute() {
+                *project_wordlist = base.join(&project_wordlist);
+            }
+        }
+
+        // word lists are project files, so they are not looked up in the search dirs
+        if let Some(ref mut extra_wordlists) = self.extra_wordlists {
+            for extra_wordlist in extra_wordlists.iter_mut() {
+                if !extra_wordlist.is_absolute() {
+                    *extra_wordlist = base.join(&extra_wordlist);
+                }
+                if !extra_wordlist.is_file() {
+                    bail!(
+                        "Extra word list {} does not exist or is not a file",
+                        extra_wordlist.display()
+                    );
+                }
+            }
+        }
+
         if let Some(ref mut search_dirs) = self.search_dirs.0 {
             *search_dirs = search_dirs
                 .iter_mut()
@@ -360,10 +418,73 @@ impl LanguageToolConfig {
     }
 }
 
+/// Checks identifiers in inline code against the symbols of the crate.
+///
+/// Disabled by default, a `[Symbols]` section enables it.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SymbolsConfig {
+    /// Maximum number of edits between an identifier in inline code and a symbol
+    /// of the crate, for the identifier to be reported as a misspelled symbol.
+    pub max_distance: Option<usize>,
+}
+
+impl SymbolsConfig {
+    const DEFAULT_MAX_DISTANCE: usize = 2;
+
+    pub fn max_distance(&self) -> usize {
+        self.max_distance.unwrap_or(Self::DEFAULT_MAX_DISTANCE)
+    }
+}
+
+/// Checks links offline, there is nothing to configure yet.
+///
+/// Disabled by default, an empty `[Links]` section enables it.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LinksConfig {}
+
+/// An external program checking the plain text of every chunk, see `checker::command`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CommandConfig {
+    /// The program to run. Plain names are looked up in `PATH`,
+    /// relative paths are relative to the configuration file.
+    pub program: PathBuf,
+    /// Arguments passed to the program.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl CommandConfig {
+    pub fn sanitize_paths(&mut self, base: &Path) {
+        if self.program.is_relative() && self.program.components().count() > 1 {
+            self.program = base.join(&self.program);
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ReflowConfig {
+    /// Column at which the paragraphs of doc comments are wrapped,
+    /// including indentation and the comment prefix.
+    pub max_line_length: Option<usize>,
+}
+
+impl ReflowConfig {
+    const DEFAULT_MAX_LINE_LENGTH: usize = 80;
+
+    pub fn max_line_length(&self) -> usize {
+        self.max_line_length
+            .unwrap_or(Self::DEFAULT_MAX_LINE_LENGTH)
+    }
+}
+
 impl Config {
-    const QUALIFIER: &'static str = "io";
-    const ORGANIZATION: &'static str = "spearow";
-    const APPLICATION: &'static str = "cargo_spellcheck";
+    pub(crate) const QUALIFIER: &'static str = "io";
+    pub(crate) const ORGANIZATION: &'static str = "spearow";
+    pub(crate) const APPLICATION: &'static str = "cargo_spellcheck";
 
     /// Sanitize all relative paths to absolute paths
     /// in relation to `base`.
@@ -371,6 +492,12 @@ impl Config {
         if let Some(ref mut hunspell) = self.hunspell {
             hunspell.sanitize_paths(base)?;
         }
+        if let Some(ref mut command) = self.command {
+            command.sanitize_paths(base);
+        }
+        if let Some(ref mut native) = self.native {
+            native.sanitize_paths(base)?;
+        }
         Ok(())
     }
 
@@ -488,11 +615,28 @@ impl Config {
         match detector {
             Detector::Hunspell => self.hunspell.is_some(),
             Detector::LanguageTool => self.languagetool.is_some(),
+            Detector::Symbols => self.symbols.is_some(),
+            Detector::Links => self.links.is_some(),
+            Detector::Command => self.command.is_some(),
+            Detector::Native => self.native.is_some(),
             #[cfg(test)]
             Detector::Dummy => true,
         }
     }
 
+    /// The word list words are added to from the interactive `fix` mode, of
+    /// the `Hunspell` or otherwise the `Native` dictionary.
+    pub fn project_wordlist(&self) -> Option<&Path> {
+        self.hunspell
+            .as_ref()
+            .and_then(HunspellConfig::project_wordlist)
+            .or_else(|| {
+                self.native
+                    .as_ref()
+                    .and_then(HunspellConfig::project_wordlist)
+            })
+    }
+
     pub fn full() -> Self {
         let languagetool = LanguageToolConfig {
             url: url::Url::parse("http://127.0.0.1:8010").expect("Default ip must be ok"),
@@ -530,13 +674,24 @@ fn os_specific_search_dirs() -> &'static [PathBuf] {
 impl Default for Config {
     fn default() -> Self {
         Self {
+            dev_comments: false,
+            string_literals: false,
             hunspell: Some(HunspellConfig {
                 lang: Some("en_US".to_owned()),
                 search_dirs: Some(os_specific_search_dirs().to_vec()).into(),
                 extra_dictonaries: Some(Vec::new()),
+                extra_wordlists: Some(Vec::new()),
                 quirks: Some(Quirks::default()),
+                project_wordlist: None,
             }),
             languagetool: None,
+            symbols: None,
+            links: None,
+            command: None,
+            native: None,
+            reflow: Some(ReflowConfig {
+                max_line_length: Some(ReflowConfig::DEFAULT_MAX_LINE_LENGTH),
+            }),
         }
     }
 }
@@ -675,6 +830,36 @@ transform_regex = ["^'([^\\s])'$", "^[0-9]+x$"]
         let _hunspell = cfg.hunspell.expect("Must contain hunspell cfg");
     }
 
+    #[test]
+    fn project_wordlist() {
+        let cfg = Config::parse(
+            r#"
+[Hunspell]
+project_wordlist = "wordlist.txt"
+			"#,
+        )
+        .unwrap();
+        let mut hunspell = cfg.hunspell.expect("Must contain hunspell cfg");
+        hunspell.sanitize_paths(Path::new("/project")).unwrap();
+        assert_eq!(
+            hunspell.project_wordlist(),
+            Some(Path::new("/project/wordlist.txt"))
+        );
+    }
+
+    #[test]
+    fn extra_wordlists_missing() {
+        let cfg = Config::parse(
+            r#"
+[Hunspell]
+extra_wordlists = ["does/not/exist.txt"]
+			"#,
+        )
+        .unwrap();
+        let mut hunspell = cfg.hunspell.expect("Must contain hunspell cfg");
+        assert!(hunspell.sanitize_paths(Path::new("/project")).is_err());
+    }
+
     #[test]
     fn partial_8() {
         let cfg = Config::parse(
@@ -12,6 +12,8 @@ use crate::{util::sub_chars, Range, Span};
 /// Definition of the source of a checkable chunk
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum ContentOrigin {
+    /// The package metadata of a `Cargo.toml` manifest.
+    CargoManifest(PathBuf),
     CommonMarkFile(PathBuf),
     RustDocTest(PathBuf, Span), // span is just there to disambiguiate
     RustSourceFile(PathBuf),
@@ -22,6 +24,7 @@ pub enum ContentOrigin {
 impl ContentOrigin {
     pub fn as_path(&self) -> &Path {
         match self {
+            Self::CargoManifest(path) => path.as_path(),
             Self::CommonMarkFile(path) => path.as_path(),
             Self::RustDocTest(path, _) => path.as_path(),
             Self::RustSourceFile(path) => path.as_path(),
@@ -42,6 +45,18 @@ impl fmt::Display for ContentOrigin {
     }
 }
 
+/// The kind of source content a chunk was extracted from.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Default)]
+pub enum ChunkKind {
+    /// Doc comments, doc attributes or markdown, as rendered by `rustdoc` or markdown tooling.
+    #[default]
+    Documentation,
+    /// Regular `//` and `/* */` comments.
+    DevComment,
+    /// String literals of user facing messages, i.e. within `panic!` or `.expect(..)`.
+    StringLiteral,
+}
+
 /// A chunk of documentation that is supposed to be checked
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CheckableChunk {
@@ -52,6 +67,10 @@ pub struct CheckableChunk {
     /// `Span` referencing the location within the source file.
     /// For a markdown file i.e. this would become a single entry spanning from start to end.
     source_mapping: IndexMap<Range, Span>,
+    /// Words which are accepted within this chunk, as declared by `#[cfg_attr(spellcheck, allow(..))]`.
+    allowed: Vec<String>,
+    /// The kind of content, anything but `Documentation` is not rendered by `rustdoc`.
+    kind: ChunkKind,
 }
 
 impl std::hash::Hash for CheckableChunk {
@@ -61,6 +80,8 @@ impl std::hash::Hash for CheckableChunk {
         self.source_mapping.iter().for_each(|t| {
             t.hash(hasher);
         });
+        self.allowed.hash(hasher);
+        self.kind.hash(hasher);
     }
 }
 
@@ -79,9 +100,51 @@ impl CheckableChunk {
         Self {
             content,
             source_mapping,
+            allowed: Vec::new(),
+            kind: ChunkKind::Documentation,
+        }
+    }
+
+    /// Set the kind of content the chunk was extracted from.
+    pub fn set_kind(&mut self, kind: ChunkKind) {
+        self.kind = kind;
+    }
+
+    /// The kind of content the chunk was extracted from.
+    pub fn kind(&self) -> ChunkKind {
+        self.kind
+    }
+
+    /// Accept the given words within this chunk.
+    pub fn allow_words(&mut self, words: impl IntoIterator<Item = String>) {
+        for word in words {
+            if !self.allowed.contains(&word) {
+                self.allowed.push(word);
+            }
         }
     }
 
+    /// Words which are accepted within this chunk.
+    pub fn allowed_words(&self) -> &[String] {
+        self.allowed.as_slice()
+    }
+
+    /// A stable hash of everything that affects the findings of a checker.
+    ///
+    /// Unlike `Hash` it does not cover the spans, so the hash is retained
+    /// if the chunk moves within or between files.
+    pub fn content_hash(&self) -> u64 {
+        let mut key = self.content.clone();
+        for range in self.source_mapping.keys() {
+            key.push_str(&format!("\0{}..{}", range.start, range.end));
+        }
+        for word in self.allowed.iter() {
+            key.push('\0');
+            key.push_str(word);
+        }
+        crate::util::fnv1a64(key.as_bytes())
+    }
+
     /// Find which part of the range maps to which span.
     /// Note that Range can very well be split into multiple fragments
     /// where each of them can be mapped to a potentially non-continuous
@@ -96,7 +159,7 @@ impl CheckableChunk {
     ///           (13,17) => (4,0)->(4,3),
     /// ]
     /// ```
-    pub(super) fn find_spans(&self, range: Range) -> IndexMap<Range, Span> {
+    pub(crate) fn find_spans(&self, range: Range) -> IndexMap<Range, Span> {
         trace!(
             "############################################ chunk find_span {:?}",
             &range
@@ -258,10 +321,21 @@ impl CheckableChunk {
 /// as well as vector of checkable chunks.
 impl From<Clusters> for Vec<CheckableChunk> {
     fn from(clusters: Clusters) -> Vec<CheckableChunk> {
+        let allowed = clusters.allowed;
+        let strings = clusters.strings.into_iter().map(|literal_set| {
+            let mut chunk = CheckableChunk::from_literalset(literal_set);
+            chunk.set_kind(ChunkKind::StringLiteral);
+            chunk
+        });
         clusters
             .set
             .into_iter()
-            .map(|literal_set| CheckableChunk::from_literalset(literal_set))
+            .map(CheckableChunk::from_literalset)
+            .chain(strings)
+            .map(|mut chunk| {
+                chunk.allow_words(allowed.iter().cloned());
+                chunk
+            })
             .collect::<Vec<_>>()
     }
 }
@@ -4,18 +4,119 @@ use super::*;
 use crate::documentation::Range;
 use crate::Span;
 use anyhow::{anyhow, Error, Result};
+use proc_macro2::Delimiter;
 use std::convert::TryFrom;
 
+/// Macros whose string literals are shown to users, using the `format!` syntax.
+const MESSAGE_MACROS: &[&str] = &[
+    "panic",
+    "unreachable",
+    "unimplemented",
+    "todo",
+    "assert",
+    "assert_eq",
+    "assert_ne",
+    "print",
+    "println",
+    "eprint",
+    "eprintln",
+    "format",
+    "format_args",
+    "write",
+    "writeln",
+    "trace",
+    "debug",
+    "info",
+    "warn",
+    "error",
+    "anyhow",
+    "bail",
+    "ensure",
+];
+
+/// Methods whose string literal argument is shown to users as is.
+const MESSAGE_METHODS: &[&str] = &["expect", "expect_err"];
+
+/// Char ranges of the escape sequences within the content of a string literal.
+fn escape_sequences(content: &str) -> Vec<Range> {
+    let mut acc = Vec::new();
+    let mut chars = content.chars().enumerate().peekable();
+    while let Some((idx, c)) = chars.next() {
+        if c != '\\' {
+            continue;
+        }
+        let end = match chars.next() {
+            // `\u{..}`
+            Some((_, 'u')) => chars
+                .by_ref()
+                .find(|(_, c)| *c == '}')
+                .map(|(end, _)| end + 1)
+                .unwrap_or(idx + 2),
+            // `\x7f`
+            Some((_, 'x')) => {
+                chars.next();
+                chars.next();
+                idx + 4
+            }
+            // a line continuation only consists of the backslash, the newline remains
+            Some((_, '\n')) => idx + 1,
+            Some(_) => idx + 2,
+            None => idx + 1,
+        };
+        acc.push(idx..end);
+    }
+    acc
+}
+
+/// Char ranges of the `{..}` placeholders and `{{`, `}}` escapes of a format string.
+fn format_placeholders(content: &str) -> Vec<Range> {
+    let mut acc = Vec::new();
+    let mut chars = content.chars().enumerate().peekable();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '{' if matches!(chars.peek(), Some((_, '{'))) => {
+                chars.next();
+                acc.push(idx..idx + 2);
+            }
+            '}' if matches!(chars.peek(), Some((_, '}'))) => {
+                chars.next();
+                acc.push(idx..idx + 2);
+            }
+            '{' => {
+                if let Some((end, _)) = chars.by_ref().find(|(_, c)| *c == '}') {
+                    acc.push(idx..end + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    acc
+}
+
 /// Cluster literals for one file
 #[derive(Debug)]
 pub struct Clusters {
     pub(super) set: Vec<LiteralSet>,
+    /// String literals of user facing messages, one set each.
+    pub(super) strings: Vec<LiteralSet>,
+    /// Whether to collect `strings` at all.
+    string_literals: bool,
+    /// Words accepted in the whole file, declared by an inner attribute.
+    pub(super) allowed: Vec<String>,
+    /// Line ranges suppressed by regular comment directives.
+    suppressed: Vec<Range>,
+    /// Doc literals of the attributes of the current item, not yet clustered.
+    pending: Vec<proc_macro2::Literal>,
+    /// Words accepted in the doc literals of the current item.
+    pending_allowed: Vec<String>,
 }
 
 impl Clusters {
     /// Only works if the file is processed line by line, otherwise
     /// requires a adjacency list.
-    fn process_literal(&mut self, source: &str, literal: proc_macro2::Literal) -> Result<()> {
+    ///
+    /// Returns the index of the set the literal was added to.
+    fn process_literal(&mut self, source: &str, literal: proc_macro2::Literal) -> Result<usize> {
         let literal = TrimmedLiteral::try_from((source, literal))?;
         if let Some(cls) = self.set.last_mut() {
             if let Err(literal) = cls.add_adjacent(literal) {
@@ -31,75 +132,273 @@ impl Clusters {
         } else {
             self.set.push(LiteralSet::from(literal));
         }
-        Ok(())
+        Ok(self.set.len() - 1)
     }
 
-    /// Helper function to parse a stream and associated the found literals
-    fn parse_token_tree(&mut self, source: &str, stream: proc_macro2::TokenStream) -> Result<()> {
+    /// Whether the literal is covered by a suppression directive.
+    fn is_suppressed(&self, literal: &proc_macro2::Literal) -> bool {
+        let line = Span::from(literal.span()).start.line;
+        self.suppressed.iter().any(|lines| lines.contains(&line))
+    }
+
+    /// Cluster all pending doc literals of one item and associate the allowed words.
+    fn flush(&mut self, source: &str) {
+        let allowed = std::mem::take(&mut self.pending_allowed);
+        let mut touched = Vec::<usize>::with_capacity(2);
+        for literal in std::mem::take(&mut self.pending) {
+            if self.is_suppressed(&literal) {
+                trace!(target: "documentation", "Suppressed doc literal {:?}", literal);
+                continue;
+            }
+            match self.process_literal(source, literal) {
+                Ok(idx) => {
+                    if !touched.contains(&idx) {
+                        touched.push(idx);
+                    }
+                }
+                Err(e) => {
+                    log::error!(
+                        "BUG: Failed to guarantee literal content/span integrity: {}",
+                        e
+                    );
+                }
+            }
+        }
+        if !allowed.is_empty() {
+            for idx in touched {
+                self.set[idx].allow_words(allowed.as_slice());
+            }
+        }
+    }
+
+    /// Collect the string literals among the arguments of a macro invocation or method call.
+    ///
+    /// Escape sequences are masked and so are placeholders if the literals are `format` strings.
+    fn collect_strings(&mut self, source: &str, stream: proc_macro2::TokenStream, format: bool) {
+        // the `target: "name"` argument of log macros
+        let mut after_target = false;
+        for tree in stream {
+            let literal = match tree {
+                TokenTree::Literal(literal) if !after_target => literal,
+                TokenTree::Ident(ident) => {
+                    after_target = ident == "target";
+                    continue;
+                }
+                TokenTree::Punct(punct) if punct.as_char() == ':' => continue,
+                _ => {
+                    after_target = false;
+                    continue;
+                }
+            };
+            let repr = literal.to_string();
+            // neither byte strings, chars nor numbers
+            if !repr.starts_with('"') && !repr.starts_with('r') {
+                continue;
+            }
+            if self.is_suppressed(&literal) {
+                continue;
+            }
+            let mut literal = match TrimmedLiteral::try_from((source, literal)) {
+                Ok(literal) => literal,
+                Err(e) => {
+                    log::error!(
+                        "BUG: Failed to guarantee literal content/span integrity: {}",
+                        e
+                    );
+                    continue;
+                }
+            };
+            let mut masked = if repr.starts_with('"') {
+                escape_sequences(literal.as_str())
+            } else {
+                Vec::new()
+            };
+            if format {
+                masked.extend(format_placeholders(literal.as_str()));
+            }
+            literal.mask(&masked);
+            trace!(target: "documentation", "Found message literal {:?}", literal);
+            self.strings.push(LiteralSet::from(literal));
+        }
+    }
+
+    /// Extract the words of `spellcheck, allow(word, other)`, the content of a `cfg_attr`.
+    fn spellcheck_allow(stream: proc_macro2::TokenStream) -> Option<Vec<String>> {
+        let mut iter = stream.into_iter();
+        match iter.next() {
+            Some(TokenTree::Ident(ident)) if ident == "spellcheck" => {}
+            _ => return None,
+        }
+        match iter.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {}
+            _ => return None,
+        }
+        match iter.next() {
+            Some(TokenTree::Ident(ident)) if ident == "allow" => {}
+            _ => return None,
+        }
+        match iter.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => Some(
+                group
+                    .stream()
+                    .into_iter()
+                    .filter_map(|tree| match tree {
+                        TokenTree::Ident(ident) => Some(ident.to_string()),
+                        // allow words which are no valid identifiers, i.e. `"don't"`
+                        TokenTree::Literal(literal) => {
+                            Some(literal.to_string().trim_matches('"').to_owned())
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Extract a doc literal from the tokens following a `doc` identifier, `= "..."`.
+    fn doc_literal(iter: &mut impl Iterator<Item = TokenTree>) -> Option<proc_macro2::Literal> {
+        // this assures the sequence is as anticipated
+        if let TokenTree::Punct(punct) = iter.next()? {
+            if punct.as_char() != '=' {
+                return None;
+            }
+            if punct.spacing() != Spacing::Alone {
+                return None;
+            }
+        } else {
+            return None;
+        }
+
+        if let TokenTree::Literal(literal) = iter.next()? {
+            trace!(target: "documentation",
+                "Found doc literal at {:?}: {:?}",
+                <Span as TryInto<Range>>::try_into(Span::from(literal.span())),
+                literal
+            );
+            Some(literal)
+        } else {
+            None
+        }
+    }
+
+    /// Parse the content of one attribute `#[..]` or `#![..]`.
+    fn parse_attribute(&mut self, stream: proc_macro2::TokenStream, inner: bool) {
         let mut iter = stream.into_iter();
         while let Some(tree) = iter.next() {
             match tree {
-                TokenTree::Ident(ident) => {
-                    // if we find an identifier
-                    // which is doc
-                    if ident != "doc" {
-                        continue;
+                TokenTree::Ident(ident) if ident == "doc" => {
+                    if let Some(literal) = Self::doc_literal(&mut iter) {
+                        self.pending.push(literal);
                     }
-
-                    // this assures the sequence is as anticipated
-                    let op = iter.next();
-                    if op.is_none() {
-                        continue;
+                }
+                TokenTree::Ident(ident) if ident == "cfg_attr" => {
+                    if let Some(TokenTree::Group(group)) = iter.next() {
+                        if let Some(words) = Self::spellcheck_allow(group.stream()) {
+                            if inner {
+                                self.allowed.extend(words);
+                            } else {
+                                self.pending_allowed.extend(words);
+                            }
+                        } else {
+                            // i.e. `cfg_attr(feature = "x", doc = "..")`
+                            self.parse_attribute(group.stream(), inner);
+                        }
                     }
-                    let op = op.unwrap();
-                    if let TokenTree::Punct(punct) = op {
-                        if punct.as_char() != '=' {
-                            continue;
+                }
+                TokenTree::Group(group) => self.parse_attribute(group.stream(), inner),
+                _ => {}
+            }
+        }
+    }
+
+    /// Helper function to parse a stream and associated the found literals
+    fn parse_token_tree(&mut self, source: &str, stream: proc_macro2::TokenStream) -> Result<()> {
+        let mut iter = stream.into_iter().peekable();
+        let mut after_dot = false;
+        while let Some(tree) = iter.next() {
+            let dot = matches!(&tree, TokenTree::Punct(punct) if punct.as_char() == '.');
+            match tree {
+                TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                    // attributes are collected until the item they annotate
+                    let inner = match iter.peek() {
+                        Some(TokenTree::Punct(punct)) if punct.as_char() == '!' => {
+                            let _ = iter.next();
+                            true
                         }
-                        if punct.spacing() != Spacing::Alone {
-                            continue;
+                        _ => false,
+                    };
+                    match iter.peek() {
+                        Some(TokenTree::Group(group))
+                            if group.delimiter() == Delimiter::Bracket =>
+                        {
+                            let stream = group.stream();
+                            let _ = iter.next();
+                            self.parse_attribute(stream, inner);
                         }
-                    } else {
-                        continue;
+                        _ => self.flush(source),
                     }
-
-                    let comment = iter.next();
-                    if comment.is_none() {
-                        continue;
+                }
+                TokenTree::Ident(ident) if ident == "doc" => {
+                    // doc literals outside of attributes, i.e. within macro invocations
+                    if let Some(literal) = Self::doc_literal(&mut iter) {
+                        self.pending.push(literal);
+                    }
+                }
+                TokenTree::Ident(ident)
+                    if self.string_literals
+                        && MESSAGE_MACROS.contains(&ident.to_string().as_str()) =>
+                {
+                    self.flush(source);
+                    if matches!(iter.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '!')
+                    {
+                        let _ = iter.next();
+                        // the group itself is traversed in the next iteration
+                        if let Some(TokenTree::Group(group)) = iter.peek() {
+                            self.collect_strings(source, group.stream(), true);
+                        }
                     }
-                    let comment = comment.unwrap();
-                    if let TokenTree::Literal(literal) = comment {
-                        trace!(target: "documentation",
-                            "Found doc literal at {:?}: {:?}",
-                            <Span as TryInto<Range>>::try_into(Span::from(literal.span())),
-                            literal
-                        );
-                        if let Err(e) = self.process_literal(source, literal) {
-                            log::error!(
-                                "BUG: Failed to guarantee literal content/span integrity: {}",
-                                e
-                            );
-                            continue;
+                }
+                TokenTree::Ident(ident)
+                    if self.string_literals
+                        && after_dot
+                        && MESSAGE_METHODS.contains(&ident.to_string().as_str()) =>
+                {
+                    self.flush(source);
+                    match iter.peek() {
+                        Some(TokenTree::Group(group))
+                            if group.delimiter() == Delimiter::Parenthesis =>
+                        {
+                            self.collect_strings(source, group.stream(), false);
                         }
-                    } else {
-                        continue;
+                        _ => {}
                     }
                 }
                 TokenTree::Group(group) => {
+                    self.flush(source);
                     self.parse_token_tree(source, group.stream())?;
                 }
-                _ => {}
+                _ => self.flush(source),
             };
+            after_dot = dot;
         }
+        self.flush(source);
         Ok(())
     }
 }
 
-impl TryFrom<&str> for Clusters {
-    type Error = Error;
-    fn try_from(source: &str) -> Result<Self> {
+impl Clusters {
+    /// Cluster the doc literals of `source` and, if `string_literals` is set,
+    /// collect the string literals of user facing messages.
+    pub fn load(source: &str, string_literals: bool) -> Result<Self> {
         let mut chunk = Self {
             set: Vec::with_capacity(64),
+            strings: Vec::new(),
+            string_literals,
+            allowed: Vec::new(),
+            suppressed: suppressed_lines(source),
+            pending: Vec::with_capacity(16),
+            pending_allowed: Vec::new(),
         };
         let stream = syn::parse_str::<proc_macro2::TokenStream>(source)
             .map_err(|e| anyhow!("Failed to parse content to stream").context(e))?;
@@ -107,3 +406,104 @@ impl TryFrom<&str> for Clusters {
         Ok(chunk)
     }
 }
+
+impl TryFrom<&str> for Clusters {
+    type Error = Error;
+    fn try_from(source: &str) -> Result<Self> {
+        Self::load(source, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignore_next_line() {
+        const SOURCE: &str = r#"
+/// Checked
+// spellcheck:ignore-next-line
+/// Ignored
+struct X;
+
+// spellcheck:off
+/// Ignored
+struct Y;
+// spellcheck:on
+
+/// Checked too
+struct Z;
+"#;
+        let chunks = Vec::<CheckableChunk>::from(Clusters::try_from(SOURCE).unwrap());
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].as_str(), " Checked");
+        assert_eq!(chunks[1].as_str(), " Checked too");
+    }
+
+    #[test]
+    fn cfg_attr_allow() {
+        const SOURCE: &str = r#"
+#![cfg_attr(spellcheck, allow(everywhere))]
+
+/// Uses foobaz.
+#[cfg_attr(spellcheck, allow(foobaz, "don't"))]
+struct X;
+
+/// Uses foobaz too.
+struct Y;
+"#;
+        let chunks = Vec::<CheckableChunk>::from(Clusters::try_from(SOURCE).unwrap());
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(
+            chunks[0].allowed_words(),
+            &["foobaz", "don't", "everywhere"]
+        );
+        assert_eq!(chunks[1].allowed_words(), &["everywhere"]);
+    }
+
+    #[test]
+    fn message_literals() {
+        const SOURCE: &str = r#####"
+/// Docs.
+fn main() {
+    let x = foo().expect("Faild to foo");
+    log::warn!(target: "quirks", "Skiping {} of {name:?}\n", x);
+    panic!(r"raw {} stays");
+    let ignored = "not a messsage";
+    println!("{{escaped}} brace");
+}
+"#####;
+        let chunks = Vec::<CheckableChunk>::from(Clusters::load(SOURCE, true).unwrap());
+        let strings = chunks
+            .iter()
+            .filter(|chunk| chunk.kind() == ChunkKind::StringLiteral)
+            .collect::<Vec<_>>();
+        assert_eq!(chunks.len(), strings.len() + 1);
+        assert_eq!(
+            strings
+                .iter()
+                .map(|chunk| chunk.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "Faild to foo".to_owned(),
+                format!("Skiping {} of {}", "  ", " ".repeat(10)),
+                "raw    stays".to_owned(),
+                "  escaped   brace".to_owned(),
+            ]
+        );
+        // masking retains the mapping onto the source
+        for chunk in strings.iter() {
+            for (range, span) in chunk.iter() {
+                assert_eq!(span.one_line_len(), Some(range.len()));
+            }
+        }
+        let (_range, span) = strings[0].iter().next().unwrap();
+        assert_eq!(
+            crate::util::load_span_from(SOURCE.as_bytes(), *span).unwrap(),
+            "Faild to foo"
+        );
+
+        let chunks = Vec::<CheckableChunk>::from(Clusters::try_from(SOURCE).unwrap());
+        assert_eq!(chunks.len(), 1);
+    }
+}
@@ -0,0 +1,378 @@
+//! Regular comments of a Rust source file
+//!
+//! `proc_macro2` only retains doc comments, as `doc` attributes, so the regular
+//! `//` and `/* */` comments, meant for developers rather than users of an API,
+//! are extracted by scanning the source. String and char literals are skipped,
+//! so comment delimiters within them are not mistaken for comments.
+//!
+//! Line comments on consecutive lines starting at the same column form one chunk,
+//! each block comment forms a chunk of its own.
+
+use super::*;
+
+/// A comment with the position of its first char after the comment delimiter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Comment {
+    start: LineColumn,
+    content: String,
+    block: bool,
+}
+
+/// Cursor over the chars of a source, tracking the line and column.
+struct Scanner<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    position: LineColumn,
+    /// The previously consumed char, to tell raw string prefixes from identifiers.
+    previous: Option<char>,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            chars: source.chars().peekable(),
+            position: LineColumn { line: 1, column: 0 },
+            previous: None,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    /// The char after the next one, without consuming anything.
+    fn peek_second(&self) -> Option<char> {
+        self.chars.clone().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 0;
+        } else {
+            self.position.column += 1;
+        }
+        self.previous = Some(c);
+        Some(c)
+    }
+
+    /// Consume everything up to, excluding, the end of the line.
+    fn line_remainder(&mut self) -> String {
+        let mut acc = String::new();
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                break;
+            }
+            acc.push(c);
+            self.bump();
+        }
+        // `\r\n` line endings
+        if acc.ends_with('\r') {
+            acc.pop();
+        }
+        acc
+    }
+
+    /// Consume a possibly nested block comment, of which `/*` was already consumed,
+    /// and return its content.
+    fn block_remainder(&mut self) -> String {
+        let mut acc = String::new();
+        let mut depth = 1usize;
+        while let Some(c) = self.bump() {
+            match (c, self.peek()) {
+                ('/', Some('*')) => {
+                    self.bump();
+                    depth += 1;
+                    acc.push_str("/*");
+                }
+                ('*', Some('/')) => {
+                    self.bump();
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                    acc.push_str("*/");
+                }
+                (c, _) => acc.push(c),
+            }
+        }
+        acc
+    }
+
+    /// Consume a string literal, of which the opening `"` was already consumed.
+    fn string(&mut self) {
+        while let Some(c) = self.bump() {
+            match c {
+                '\\' => {
+                    self.bump();
+                }
+                '"' => break,
+                _ => {}
+            }
+        }
+    }
+
+    /// Consume a raw string literal, of which `r` was already consumed.
+    fn raw_string(&mut self) {
+        let mut hashes = 0usize;
+        while self.peek() == Some('#') {
+            self.bump();
+            hashes += 1;
+        }
+        if self.bump() != Some('"') {
+            return;
+        }
+        while let Some(c) = self.bump() {
+            if c == '"' {
+                let mut closing = 0usize;
+                while closing < hashes && self.peek() == Some('#') {
+                    self.bump();
+                    closing += 1;
+                }
+                if closing == hashes {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Consume a char literal or a lifetime, of which `'` was already consumed.
+    fn char_or_lifetime(&mut self) {
+        match (self.peek(), self.peek_second()) {
+            (Some('\\'), _) => {
+                self.bump();
+                self.bump();
+                while let Some(c) = self.bump() {
+                    if c == '\'' || c == '\n' {
+                        break;
+                    }
+                }
+            }
+            (Some(_), Some('\'')) => {
+                self.bump();
+                self.bump();
+            }
+            // a lifetime or label, which is an identifier
+            _ => {}
+        }
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// All regular comments of `source`, excluding doc comments.
+fn comments(source: &str) -> Vec<Comment> {
+    let mut acc = Vec::new();
+    let mut scanner = Scanner::new(source);
+    while let Some(c) = scanner.peek() {
+        let previous = scanner.previous;
+        scanner.bump();
+        match (c, scanner.peek()) {
+            ('/', Some('/')) => {
+                scanner.bump();
+                let start = scanner.position;
+                let content = scanner.line_remainder();
+                // `///` and `//!` are doc comments, yet `////` is not
+                let doc = (content.starts_with('/') && !content.starts_with("//"))
+                    || content.starts_with('!');
+                if !doc {
+                    acc.push(Comment {
+                        start,
+                        content,
+                        block: false,
+                    });
+                }
+            }
+            ('/', Some('*')) => {
+                scanner.bump();
+                let start = scanner.position;
+                let content = scanner.block_remainder();
+                // `/**` and `/*!` are doc comments, yet `/***` and `/**/` are not
+                let doc = (content.starts_with('*') && !content.starts_with("**"))
+                    || content.starts_with('!');
+                if !doc && !content.is_empty() {
+                    acc.push(Comment {
+                        start,
+                        content,
+                        block: true,
+                    });
+                }
+            }
+            ('"', _) => scanner.string(),
+            ('r', Some('"')) | ('r', Some('#'))
+                if !previous.map(is_ident_char).unwrap_or(false) || previous == Some('b') =>
+            {
+                scanner.raw_string()
+            }
+            ('\'', _) => scanner.char_or_lifetime(),
+            _ => {}
+        }
+    }
+    acc
+}
+
+/// The span covering `content` when starting at `start`.
+///
+/// An empty `content` yields a span ending right before it starts, so its length is zero.
+fn span_of(start: LineColumn, content: &str) -> Span {
+    let mut position = start;
+    let mut end = LineColumn {
+        line: start.line,
+        column: start.column.saturating_sub(1),
+    };
+    for c in content.chars() {
+        end = position;
+        if c == '\n' {
+            position.line += 1;
+            position.column = 0;
+        } else {
+            position.column += 1;
+        }
+    }
+    Span { start, end }
+}
+
+/// Chunks of all regular comments of the Rust `source`, excluding doc comments,
+/// `spellcheck:` directives and the comments suppressed by them.
+pub fn dev_comment_chunks(source: &str) -> Vec<CheckableChunk> {
+    let mut acc = Vec::new();
+    let mut lines: Vec<Comment> = Vec::new();
+    let suppressed = suppressed_lines(source);
+
+    let flush = |lines: &mut Vec<Comment>, acc: &mut Vec<CheckableChunk>| {
+        if lines.is_empty() {
+            return;
+        }
+        let mut content = String::new();
+        let mut source_mapping = IndexMap::with_capacity(lines.len());
+        for comment in lines.drain(..) {
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            let start = content.chars().count();
+            content.push_str(&comment.content);
+            let end = content.chars().count();
+            source_mapping.insert(start..end, span_of(comment.start, &comment.content));
+        }
+        acc.push(CheckableChunk::from_string(content, source_mapping));
+    };
+
+    for comment in comments(source) {
+        if comment.content.trim_start().starts_with("spellcheck:") {
+            continue;
+        }
+        if suppressed
+            .iter()
+            .any(|lines| lines.contains(&comment.start.line))
+        {
+            continue;
+        }
+        if comment.block {
+            flush(&mut lines, &mut acc);
+            let source_mapping = indexmap::indexmap! {
+                0..comment.content.chars().count() => span_of(comment.start, &comment.content)
+            };
+            acc.push(CheckableChunk::from_string(comment.content, source_mapping));
+            continue;
+        }
+        let adjacent = lines
+            .last()
+            .map(|last| {
+                last.start.line + 1 == comment.start.line
+                    && last.start.column == comment.start.column
+            })
+            .unwrap_or(true);
+        if !adjacent {
+            flush(&mut lines, &mut acc);
+        }
+        lines.push(comment);
+    }
+    flush(&mut lines, &mut acc);
+    acc.iter_mut()
+        .for_each(|chunk| chunk.set_kind(ChunkKind::DevComment));
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::load_span_from;
+
+    const SOURCE: &str = r####"//! Crate docs are no developer comments.
+
+// Regular comment
+// on two lines.
+/// Doc comment.
+fn foo<'a>(x: &'a str) -> char {
+    let s = "// not a comment /* either */";
+    let r = r#"also " // not"#;
+    //// Four slashes are regular.
+    let c = '"'; // trailing
+    /* block /* nested */ comment */
+    '\''
+}
+"####;
+
+    #[test]
+    fn extract_comments() {
+        let chunks = dev_comment_chunks(SOURCE);
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.kind() == ChunkKind::DevComment));
+        let contents = chunks
+            .iter()
+            .map(|chunk| chunk.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            contents,
+            vec![
+                " Regular comment\n on two lines.",
+                "// Four slashes are regular.",
+                " trailing",
+                " block /* nested */ comment ",
+            ]
+        );
+
+        // every fragment maps onto its exact source
+        for chunk in chunks.iter() {
+            for (range, span) in chunk.iter() {
+                let loaded = load_span_from(SOURCE.as_bytes(), *span).unwrap();
+                assert_eq!(
+                    loaded,
+                    crate::util::sub_chars(chunk.as_str(), range.clone())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn empty_comment_lines() {
+        let chunks = dev_comment_chunks("fn foo() {}\n// first\n//\n// second\n");
+        assert_eq!(chunks.len(), 1);
+        let chunk = &chunks[0];
+        assert_eq!(chunk.as_str(), " first\n\n second");
+        let spans = chunk.iter().map(|(_range, span)| *span).collect::<Vec<_>>();
+        assert_eq!(spans[1].one_line_len(), Some(0));
+        assert_eq!(spans[2].start, LineColumn { line: 4, column: 2 });
+    }
+
+    #[test]
+    fn directives_are_skipped() {
+        assert!(dev_comment_chunks("// spellcheck:ignore-next-line\nfn foo() {}\n").is_empty());
+    }
+
+    #[test]
+    fn suppressed_comments() {
+        const SOURCE: &str = "// spellcheck:off\n// A commnt typo.\n// spellcheck:on\n// Checked.\n// spellcheck:ignore-next-line\nlet x = 1; // Ignord too.\n";
+        let chunks = dev_comment_chunks(SOURCE);
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|chunk| chunk.as_str())
+                .collect::<Vec<_>>(),
+            vec![" Checked."]
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Inline suppression directives
+//!
+//! Allow to silence false positives without disabling a whole checker.
+//!
+//! * `// spellcheck:ignore-next-line` as regular comment ignores the doc comment of the next line
+//! * `// spellcheck:off` and `// spellcheck:on` as regular comments ignore all doc comments in between
+//! * `<!-- spellcheck:ignore-next-line -->`, `<!-- spellcheck:off -->` and `<!-- spellcheck:on -->`
+//!   do the same within markdown, be it a doc comment or a markdown file
+//! * `#[cfg_attr(spellcheck, allow(word, other))]` accepts the listed words in the doc comments
+//!   of the annotated item, or in the whole file if used as inner attribute `#![..]`
+
+use crate::Range;
+
+use pulldown_cmark::{Event, Options, Parser};
+
+/// A single suppression directive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Directive {
+    /// Ignore the next line.
+    IgnoreNextLine,
+    /// Ignore everything until the next `On`.
+    Off,
+    /// Resume checking.
+    On,
+}
+
+impl Directive {
+    const PREFIX: &'static str = "spellcheck:";
+
+    /// Parse the text of a comment, without comment markers.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if !text.starts_with(Self::PREFIX) {
+            return None;
+        }
+        match text[Self::PREFIX.len()..].trim() {
+            "ignore-next-line" => Some(Self::IgnoreNextLine),
+            "off" => Some(Self::Off),
+            "on" => Some(Self::On),
+            _ => None,
+        }
+    }
+
+    /// Parse a html comment `<!-- spellcheck:.. -->`.
+    pub fn parse_html_comment(html: &str) -> Option<Self> {
+        let html = html.trim();
+        if html.starts_with("<!--") && html.ends_with("-->") {
+            Self::parse(&html[4..html.len() - 3])
+        } else {
+            None
+        }
+    }
+
+    /// Parse a source line, which only consists of a regular, non-doc comment.
+    pub fn parse_line_comment(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.starts_with("//") {
+            // `///` and `//!` are doc comments, `////` is not
+            if (line.starts_with("///") && !line.starts_with("////")) || line.starts_with("//!") {
+                return None;
+            }
+            Self::parse(line.trim_start_matches('/'))
+        } else if line.starts_with("/*") && line.ends_with("*/") {
+            if (line.starts_with("/**") && !line.starts_with("/***")) || line.starts_with("/*!") {
+                return None;
+            }
+            Self::parse(&line[2..line.len() - 2])
+        } else {
+            None
+        }
+    }
+}
+
+/// Determine the 1-indexed, exclusive ranges of lines which are suppressed
+/// by regular comment directives within `source`.
+pub fn suppressed_lines(source: &str) -> Vec<Range> {
+    let mut suppressed = Vec::new();
+    let mut off_since: Option<usize> = None;
+    for (lineno, line) in source.lines().enumerate() {
+        let lineno = lineno + 1;
+        match Directive::parse_line_comment(line) {
+            Some(Directive::IgnoreNextLine) => suppressed.push((lineno + 1)..(lineno + 2)),
+            Some(Directive::Off) if off_since.is_none() => off_since = Some(lineno),
+            Some(Directive::On) if off_since.is_some() => {
+                let start = off_since.take().expect("Checked in guard. qed");
+                suppressed.push(start..lineno);
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = off_since {
+        suppressed.push(start..usize::MAX);
+    }
+    suppressed
+}
+
+/// Determine the byte ranges of the markdown `cmark` which are suppressed
+/// by html comment directives.
+pub fn suppressed_ranges(cmark: &str) -> Vec<Range> {
+    let mut suppressed = Vec::new();
+    let mut off_since: Option<usize> = None;
+    for (event, offset) in Parser::new_ext(cmark, Options::all()).into_offset_iter() {
+        let html = match event {
+            Event::Html(html) => html,
+            _ => continue,
+        };
+        match Directive::parse_html_comment(&html) {
+            Some(Directive::Off) if off_since.is_none() => off_since = Some(offset.end),
+            Some(Directive::On) => {
+                if let Some(start) = off_since.take() {
+                    suppressed.push(start..offset.start);
+                }
+            }
+            Some(Directive::IgnoreNextLine) => {
+                // html blocks include their trailing newline, inline html does not
+                let start = if cmark[..offset.end].ends_with('\n') {
+                    offset.end
+                } else {
+                    cmark[offset.end..]
+                        .find('\n')
+                        .map(|idx| offset.end + idx + 1)
+                        .unwrap_or_else(|| cmark.len())
+                };
+                let end = cmark[start..]
+                    .find('\n')
+                    .map(|idx| start + idx)
+                    .unwrap_or_else(|| cmark.len());
+                suppressed.push(start..end);
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = off_since {
+        suppressed.push(start..cmark.len());
+    }
+    suppressed
+}
+
+/// Replace all occurrences of the `words` in `s` with spaces, if they are not part of a longer word.
+///
+/// Every masked character is replaced by as many spaces as it has bytes, so the byte
+/// offsets of the plain to markdown mapping stay valid. Character offsets after a
+/// masked non-ASCII word do shift.
+pub fn mask_words(s: &mut String, words: &[String]) {
+    if words.is_empty() {
+        return;
+    }
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_' || c == '\'';
+    let mut masked = String::with_capacity(s.len());
+    let mut word = String::new();
+    let flush = |word: &mut String, masked: &mut String| {
+        if words.iter().any(|allowed| allowed == word) {
+            masked.push_str(&" ".repeat(word.len()));
+        } else {
+            masked.push_str(word);
+        }
+        word.clear();
+    };
+    for c in s.chars() {
+        if is_word_char(c) {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut masked);
+            masked.push(c);
+        }
+    }
+    flush(&mut word, &mut masked);
+    *s = masked;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documentation::cluster::Clusters;
+    use crate::documentation::CheckableChunk;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Directive::parse_line_comment("    // spellcheck:ignore-next-line"),
+            Some(Directive::IgnoreNextLine)
+        );
+        assert_eq!(
+            Directive::parse_line_comment("/* spellcheck:off */"),
+            Some(Directive::Off)
+        );
+        assert_eq!(Directive::parse_line_comment("/// spellcheck:off"), None);
+        assert_eq!(Directive::parse_line_comment("//! spellcheck:off"), None);
+        assert_eq!(
+            Directive::parse_html_comment("<!-- spellcheck:on -->\n"),
+            Some(Directive::On)
+        );
+        assert_eq!(
+            Directive::parse_html_comment("<!-- spellcheck:bogus -->"),
+            None
+        );
+    }
+
+    #[test]
+    fn lines() {
+        const SOURCE: &str = r#"
+// spellcheck:ignore-next-line
+/// Ignored
+/// Checked
+// spellcheck:off
+/// Ignored
+// spellcheck:on
+/// Checked
+"#;
+        assert_eq!(suppressed_lines(SOURCE), vec![3..4, 5..7]);
+    }
+
+    #[test]
+    fn mask() {
+        let mut s = "Use foo_bar, not foo or foobar.".to_owned();
+        mask_words(&mut s, &["foo".to_owned(), "foo_bar".to_owned()]);
+        assert_eq!(s, "Use        , not     or foobar.");
+    }
+
+    #[test]
+    fn mask_non_ascii() {
+        const SOURCE: &str = r#"
+/// A naïve mistkae.
+#[cfg_attr(spellcheck, allow("naïve"))]
+struct X;
+"#;
+        let chunks = Vec::<CheckableChunk>::from(Clusters::try_from(SOURCE).unwrap());
+        let plain = chunks[0].erase_markdown();
+        assert_eq!(plain.as_str(), "A        mistkae.");
+        let start = plain.as_str().find("mistkae").unwrap();
+        let (range, _span) = plain
+            .find_spans(start..start + "mistkae".len())
+            .into_iter()
+            .next()
+            .expect("Mistake must map back to the chunk");
+        assert_eq!(&chunks[0].as_str()[range], "mistkae");
+    }
+}
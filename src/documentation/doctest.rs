@@ -0,0 +1,350 @@
+//! Rust code blocks within documentation
+//!
+//! Fenced code blocks with Rust code, in doc comments as well as in markdown files,
+//! are parsed as if they were source files on their own, so the documentation,
+//! the regular comments and the message strings within the examples are checked too,
+//! all of them are rendered. The spans of the resulting chunks are mapped back through
+//! the enclosing chunk, so they refer to the file containing the block.
+
+use super::*;
+
+use log::debug;
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+
+/// Attributes of a code block understood by rustdoc, besides the language.
+const RUSTDOC_ATTRIBUTES: &[&str] = &[
+    "ignore",
+    "should_panic",
+    "no_run",
+    "compile_fail",
+    "test_harness",
+    "allow_fail",
+    "edition2015",
+    "edition2018",
+    "edition2021",
+    "edition2024",
+];
+
+/// Whether the info string of a fence declares Rust code.
+///
+/// Within doc comments rustdoc treats blocks without a language as Rust.
+fn is_rust_fence(info: &str, doc_comment: bool) -> bool {
+    let mut attributes = info
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|attribute| !attribute.is_empty())
+        .peekable();
+    if attributes.peek().is_none() {
+        return doc_comment;
+    }
+    let mut rust = doc_comment;
+    for attribute in attributes {
+        match attribute {
+            "rust" => rust = true,
+            attribute if RUSTDOC_ATTRIBUTES.contains(&attribute) => {}
+            _ => return false,
+        }
+    }
+    rust
+}
+
+/// The code of a fenced block with the chunk offsets of its lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CodeBlock {
+    code: String,
+    /// Char offset within the chunk for the start of each line of `code`.
+    line_offsets: Vec<usize>,
+}
+
+impl CodeBlock {
+    /// Convert a `Span` within the code to the range within the chunk.
+    fn to_chunk_range(&self, span: Span) -> Option<Range> {
+        let start = self.line_offsets.get(span.start.line.checked_sub(1)?)? + span.start.column;
+        let end = self.line_offsets.get(span.end.line.checked_sub(1)?)? + span.end.column;
+        Some(start..(end + 1))
+    }
+}
+
+/// Find all Rust code blocks of a chunk.
+fn code_blocks(chunk: &CheckableChunk, doc_comment: bool) -> Vec<CodeBlock> {
+    let content = chunk.as_str();
+    let mut blocks = Vec::new();
+    for (event, offset) in Parser::new_ext(content, Options::all()).into_offset_iter() {
+        let info = match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => info,
+            _ => continue,
+        };
+        if !is_rust_fence(&info, doc_comment) {
+            continue;
+        }
+
+        // the block range starts at the fence, excluding its indentation
+        let line_start = content[..offset.start]
+            .rfind('\n')
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+        let mut lines = content[line_start..offset.end].split('\n');
+        let fence = lines.next().unwrap_or_default();
+        let indentation = fence.chars().take_while(|c| *c == ' ').count();
+        let fence_marker = fence.trim_start().chars().next().unwrap_or('`');
+
+        let mut offset = content[..line_start].chars().count() + fence.chars().count() + 1;
+        let mut code = Vec::new();
+        let mut line_offsets = Vec::new();
+        for line in lines {
+            let len = line.chars().count();
+            if line.trim_start().starts_with(fence_marker) && line.trim().len() >= 3 {
+                // closing fence
+                break;
+            }
+            let strip = line
+                .chars()
+                .take(indentation)
+                .take_while(|c| *c == ' ')
+                .count();
+            let mut line = line.chars().skip(strip).collect::<String>();
+            // lines hidden from the rendered doc test, i.e. `# use foo;`
+            if doc_comment && (line.trim() == "#" || line.trim_start().starts_with("# ")) {
+                line = line.replacen('#', " ", 1);
+            }
+            code.push(line);
+            line_offsets.push(offset + strip);
+            offset += len + 1;
+        }
+        blocks.push(CodeBlock {
+            code: code.join("\n"),
+            line_offsets,
+        });
+    }
+    blocks
+}
+
+/// Extract the chunks of all Rust code blocks within `chunk`, which originates from `origin`,
+/// optionally including their regular comments and the string literals of user facing messages.
+///
+/// Each code block yields its own `ContentOrigin::RustDocTest`, carrying the span of the block.
+pub fn doc_tests(
+    origin: &ContentOrigin,
+    chunk: &CheckableChunk,
+    dev_comments: bool,
+    string_literals: bool,
+) -> Vec<(ContentOrigin, Vec<CheckableChunk>)> {
+    let doc_comment = matches!(
+        origin,
+        ContentOrigin::RustSourceFile(_) | ContentOrigin::RustDocTest(_, _)
+    );
+    code_blocks(chunk, doc_comment)
+        .into_iter()
+        .filter_map(|block| {
+            let clusters = match Clusters::load(block.code.as_str(), string_literals) {
+                Ok(clusters) => clusters,
+                Err(e) => {
+                    debug!("Failed to parse code block in {}: {}", origin, e);
+                    return None;
+                }
+            };
+
+            // lines are mapped one by one, ranges spanning multiple fragments are not resolved
+            let line_spans = block
+                .line_offsets
+                .iter()
+                .zip(block.code.split('\n'))
+                .filter_map(|(offset, line)| {
+                    let len = line.chars().count();
+                    chunk
+                        .find_spans(*offset..(offset + len))
+                        .values()
+                        .next()
+                        .copied()
+                })
+                .collect::<Vec<_>>();
+            let block_span = Span {
+                start: line_spans.first()?.start,
+                end: line_spans.last()?.end,
+            };
+
+            let mut inner_chunks = Vec::<CheckableChunk>::from(clusters);
+            if dev_comments {
+                inner_chunks.extend(dev_comment_chunks(&block.code));
+            }
+            let chunks = inner_chunks
+                .into_iter()
+                .map(|inner| {
+                    let source_mapping = inner
+                        .iter()
+                        .filter(|(range, _span)| !range.is_empty())
+                        .filter_map(|(range, span)| {
+                            let mapped = chunk.find_spans(block.to_chunk_range(*span)?);
+                            if mapped.len() == 1 {
+                                Some((range.clone(), *mapped.values().next()?))
+                            } else {
+                                debug!(
+                                    "Failed to map code block fragment {:?} of {}",
+                                    span, origin
+                                );
+                                None
+                            }
+                        })
+                        .collect::<IndexMap<Range, Span>>();
+                    let mut mapped =
+                        CheckableChunk::from_string(inner.as_str().to_owned(), source_mapping);
+                    mapped.allow_words(inner.allowed_words().iter().cloned());
+                    mapped.allow_words(chunk.allowed_words().iter().cloned());
+                    mapped.set_kind(inner.kind());
+                    mapped
+                })
+                .collect::<Vec<_>>();
+
+            if chunks.is_empty() {
+                None
+            } else {
+                Some((
+                    ContentOrigin::RustDocTest(origin.as_path().to_owned(), block_span),
+                    chunks,
+                ))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn fences() {
+        assert!(is_rust_fence("rust", false));
+        assert!(is_rust_fence("rust,ignore", false));
+        assert!(is_rust_fence("", true));
+        assert!(is_rust_fence("no_run", true));
+        assert!(!is_rust_fence("", false));
+        assert!(!is_rust_fence("no_run", false));
+        assert!(!is_rust_fence("toml", true));
+        assert!(!is_rust_fence("text", true));
+    }
+
+    #[test]
+    fn doc_comment_example() {
+        const SOURCE: &str = r#"
+/// Example
+///
+/// ```rust
+/// # use foo::Bar;
+/// /// Nested docz.
+/// struct X;
+/// ```
+struct Y;
+"#;
+        let origin = ContentOrigin::RustSourceFile(PathBuf::from("/tmp/lib.rs"));
+        let chunks = Vec::<CheckableChunk>::from(Clusters::try_from(SOURCE).unwrap());
+        assert_eq!(chunks.len(), 1);
+
+        let tests = doc_tests(&origin, &chunks[0], false, false);
+        assert_eq!(tests.len(), 1);
+        let (test_origin, test_chunks) = &tests[0];
+        assert_eq!(
+            test_origin,
+            &ContentOrigin::RustDocTest(
+                PathBuf::from("/tmp/lib.rs"),
+                Span {
+                    start: LineColumn { line: 5, column: 4 },
+                    end: LineColumn {
+                        line: 7,
+                        column: 12
+                    },
+                }
+            )
+        );
+        assert_eq!(test_chunks.len(), 1);
+        let inner = &test_chunks[0];
+        assert_eq!(inner.as_str(), " Nested docz.");
+
+        // ` docz` within ` Nested docz.` maps to the position in the outer file
+        let spans = inner.find_spans(8..12);
+        assert_eq!(
+            spans.values().next(),
+            Some(&Span {
+                start: LineColumn {
+                    line: 6,
+                    column: 15
+                },
+                end: LineColumn {
+                    line: 6,
+                    column: 18
+                },
+            })
+        );
+        assert_eq!(
+            load_span_from(SOURCE.as_bytes(), *spans.values().next().unwrap()).unwrap(),
+            "docz"
+        );
+    }
+
+    #[test]
+    fn comments_and_messages() {
+        const SOURCE: &str = r#"
+/// ```
+/// // Exampel comment.
+/// let x = foo().expect("Faild to foo");
+/// ```
+struct Y;
+"#;
+        let origin = ContentOrigin::RustSourceFile(PathBuf::from("/tmp/lib.rs"));
+        let chunks = Vec::<CheckableChunk>::from(Clusters::try_from(SOURCE).unwrap());
+        // neither comments nor messages unless configured
+        assert!(doc_tests(&origin, &chunks[0], false, false).is_empty());
+
+        let tests = doc_tests(&origin, &chunks[0], true, true);
+        assert_eq!(tests.len(), 1);
+        let inner = tests[0]
+            .1
+            .iter()
+            .map(|chunk| (chunk.kind(), chunk.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            inner,
+            vec![
+                (ChunkKind::StringLiteral, "Faild to foo"),
+                (ChunkKind::DevComment, " Exampel comment."),
+            ]
+        );
+        let comment = tests[0]
+            .1
+            .iter()
+            .find(|chunk| chunk.kind() == ChunkKind::DevComment)
+            .unwrap();
+        let (_range, span) = comment.iter().next().unwrap();
+        assert_eq!(
+            load_span_from(SOURCE.as_bytes(), *span).unwrap(),
+            " Exampel comment."
+        );
+    }
+
+    #[test]
+    fn markdown_example() {
+        const README: &str =
+            "# Title\n\n```rust\n/// Documented.\nfn f() {}\n```\n\n```\n/// Not rust.\n```\n";
+        let origin = ContentOrigin::CommonMarkFile(PathBuf::from("/tmp/README.md"));
+        let chunk = CheckableChunk::from_str(
+            README,
+            indexmap::indexmap! { 0..README.chars().count() => Span {
+                start: LineColumn { line: 1, column: 0 },
+                end: LineColumn { line: 10, column: 3 },
+            }},
+        );
+        let tests = doc_tests(&origin, &chunk, false, false);
+        assert_eq!(tests.len(), 1);
+        let inner = &tests[0].1[0];
+        assert_eq!(inner.as_str(), " Documented.");
+        assert_eq!(
+            inner.iter().next().unwrap().1,
+            &Span {
+                start: LineColumn { line: 4, column: 3 },
+                end: LineColumn {
+                    line: 4,
+                    column: 14
+                },
+            }
+        );
+    }
+}
@@ -100,7 +100,7 @@ impl TryFrom<(&str, proc_macro2::Literal)> for TrimmedLiteral {
 
             //^r(#+?)"(?:.*\s*)+(?=(?:"\1))("\1)$
             lazy_static::lazy_static! {
-                static ref BOUNDED_RAW_STR: Regex = Regex::new(r##"^(r(#+)?")(?:.*\s*)+?(?=(?:"\2))("\2)\s*\]?\s*$"##).expect("BOUNEDED_RAW_STR regex compiles");
+                static ref BOUNDED_RAW_STR: Regex = Regex::new(r##"^(r(#*)")(?:.*\s*)+?(?=(?:"\2))("\2)\s*\]?\s*$"##).expect("BOUNEDED_RAW_STR regex compiles");
                 static ref BOUNDED_STR: Regex = Regex::new(r##"^"(?:.(?!"\\"))*?"*\s*\]?\s*"$"##).expect("BOUNEDED_STR regex compiles");
             };
 
@@ -204,6 +204,29 @@ impl TrimmedLiteral {
         self.as_str().chars()
     }
 
+    /// Replace the chars within the given char `ranges` of the content by spaces.
+    ///
+    /// The content keeps its length in chars and thus stays aligned with the span,
+    /// i.e. to hide escape sequences from the checkers.
+    pub fn mask(&mut self, ranges: &[Range]) {
+        if ranges.is_empty() {
+            return;
+        }
+        let masked = self
+            .chars()
+            .enumerate()
+            .map(|(idx, c)| {
+                if c != '\n' && ranges.iter().any(|range| range.contains(&idx)) {
+                    ' '
+                } else {
+                    c
+                }
+            })
+            .collect::<String>();
+        self.rendered = format!("{}{}{}", self.prefix(), masked, self.suffix());
+        self.len_in_bytes = masked.len();
+    }
+
     /// Display helper, mostly used for debug investigations
     #[allow(unused)]
     pub(crate) fn display(&self, highlight: Range) -> TrimmedLiteralDisplay {
@@ -9,6 +9,8 @@ pub struct LiteralSet {
     literals: Vec<TrimmedLiteral>,
     /// lines spanned (start, end) inclusive
     pub coverage: (usize, usize),
+    /// words accepted within this set
+    allowed: Vec<String>,
 }
 
 impl LiteralSet {
@@ -17,9 +19,15 @@ impl LiteralSet {
         Self {
             coverage: (literal.span().start.line, literal.span().end.line),
             literals: vec![literal],
+            allowed: Vec::new(),
         }
     }
 
+    /// Accept the given words within the set.
+    pub fn allow_words(&mut self, words: &[String]) {
+        self.allowed.extend_from_slice(words);
+    }
+
     /// Add a literal to a literal set, if the previous lines literal already exists.
     ///
     /// Returns literl within the Err variant if not adjacent
@@ -84,7 +92,9 @@ impl LiteralSet {
                 }
             }
         }
-        CheckableChunk::from_string(content, source_mapping)
+        let mut chunk = CheckableChunk::from_string(content, source_mapping);
+        chunk.allow_words(self.allowed);
+        chunk
     }
 }
 
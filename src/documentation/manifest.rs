@@ -0,0 +1,145 @@
+//! Package metadata of a `Cargo.toml` manifest
+//!
+//! The `description` as well as every entry of `keywords` and `categories`
+//! of the `[package]` section become a chunk of their own, with spans pointing
+//! at the string values within the manifest.
+
+use super::*;
+
+use anyhow::Result;
+use log::debug;
+use serde::Deserialize;
+use toml::Spanned;
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    package: Option<Package>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Package {
+    description: Option<Spanned<String>>,
+    #[serde(default)]
+    keywords: Vec<Spanned<String>>,
+    #[serde(default)]
+    categories: Vec<Spanned<String>>,
+}
+
+/// Line and column of the char at byte `offset` within `content`.
+fn line_column(content: &str, offset: usize) -> LineColumn {
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+    LineColumn {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count(),
+    }
+}
+
+/// Create the chunk of a string `value`, spanning the given bytes of `content` including quotes.
+///
+/// Values containing escape sequences do not map char by char onto the
+/// manifest and yield no chunk.
+fn value_chunk(content: &str, value: &Spanned<String>) -> Option<CheckableChunk> {
+    let (start, end) = (value.start(), value.end());
+    let raw = content.get(start..end)?;
+    let delimiter = if raw.starts_with("\"\"\"") || raw.starts_with("'''") {
+        3
+    } else {
+        1
+    };
+    let mut inner = raw.get(delimiter..raw.len().checked_sub(delimiter)?)?;
+    let mut inner_start = start + delimiter;
+    // a newline right after the opening delimiter of a multiline string is trimmed
+    if delimiter == 3 {
+        for newline in &["\r\n", "\n"] {
+            if let Some(stripped) = inner.strip_prefix(newline) {
+                inner = stripped;
+                inner_start += newline.len();
+                break;
+            }
+        }
+    }
+    let value = value.get_ref();
+    if value.is_empty() {
+        return None;
+    }
+    if inner != value {
+        debug!("Skipping manifest value {:?} with escape sequences", value);
+        return None;
+    }
+    let last = value.chars().last()?;
+    let span = Span {
+        start: line_column(content, inner_start),
+        end: line_column(content, inner_start + value.len() - last.len_utf8()),
+    };
+    let source_mapping = indexmap::indexmap! {
+        0..value.chars().count() => span
+    };
+    Some(CheckableChunk::from_str(value, source_mapping))
+}
+
+/// All chunks of the package metadata within the manifest `content`.
+pub fn manifest_chunks(content: &str) -> Result<Vec<CheckableChunk>> {
+    let manifest: Manifest = toml::from_str(content)?;
+    let package = match manifest.package {
+        Some(package) => package,
+        None => return Ok(Vec::new()),
+    };
+    Ok(package
+        .description
+        .iter()
+        .chain(package.keywords.iter())
+        .chain(package.categories.iter())
+        .filter_map(|value| value_chunk(content, value))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"[package]
+name = "example"
+description = """
+A libary to
+check "things"."""
+keywords = ["spelling", 'dokumentation', "esc\u0061ped"]
+categories = ["development-tools"]
+"#;
+
+    #[test]
+    fn metadata_spans() {
+        let chunks = manifest_chunks(MANIFEST).expect("Manifest is valid");
+        let found = chunks
+            .iter()
+            .map(|chunk| {
+                let (_range, span) = chunk.iter().next().expect("Chunk has one fragment");
+                (
+                    chunk.as_str(),
+                    crate::util::load_span_from(MANIFEST.as_bytes(), *span).unwrap(),
+                    span.start.line,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "A libary to\ncheck \"things\".",
+                    "A libary to\ncheck \"things\".".to_owned(),
+                    4
+                ),
+                ("spelling", "spelling".to_owned(), 6),
+                ("dokumentation", "dokumentation".to_owned(), 6),
+                ("development-tools", "development-tools".to_owned(), 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_package() {
+        assert!(manifest_chunks("[workspace]\nmembers = []\n")
+            .expect("Manifest is valid")
+            .is_empty());
+    }
+}
@@ -5,7 +5,7 @@
 use super::*;
 
 use indexmap::IndexMap;
-use log::trace;
+use log::{trace, warn};
 use pulldown_cmark::{Event, Options, Parser, Tag};
 
 use crate::documentation::{CheckableChunk, Range};
@@ -58,11 +58,19 @@ impl<'a> PlainOverlay<'a> {
 
         let mut code_block = false;
 
+        // `<!-- spellcheck:off -->` until `<!-- spellcheck:on -->` and the line
+        // following `<!-- spellcheck:ignore-next-line -->`
+        let suppressed = suppressed_ranges(cmark);
+        let skip = |offset: &Range| {
+            suppressed
+                .iter()
+                .any(|suppressed| suppressed.contains(&offset.start))
+        };
+
         for (event, offset) in parser.into_offset_iter() {
             trace!("Parsing event ({:?}): {:?}", &offset, &event);
             match event {
                 Event::Start(tag) => {
-                    // @todo check links
                     match tag {
                         Tag::CodeBlock(fenced) => {
                             code_block = true;
@@ -77,11 +85,11 @@ impl<'a> PlainOverlay<'a> {
                 }
                 Event::End(tag) => {
                     match tag {
-                        Tag::Link(_link_type, _url, title) => {
-                            // @todo check links
-                            Self::track(&title, offset, &mut plain, &mut mapping);
-                        }
-                        Tag::Image(_link_type, _url, title) => {
+                        // links are verified by `checker::links`
+                        Tag::Link(_link_type, _url, title)
+                        | Tag::Image(_link_type, _url, title)
+                            if !skip(&offset) =>
+                        {
                             Self::track(&title, offset, &mut plain, &mut mapping);
                         }
                         Tag::Heading(_n) => {
@@ -99,7 +107,7 @@ impl<'a> PlainOverlay<'a> {
                     }
                 }
                 Event::Text(s) => {
-                    if code_block {
+                    if code_block || skip(&offset) {
                     } else {
                         Self::track(&s, offset, &mut plain, &mut mapping);
                     }
@@ -109,7 +117,9 @@ impl<'a> PlainOverlay<'a> {
                     // future potentially also check var names with leviatan distance
                     // to wordbook entries, and only complain if there are sane suggestions
                 }
-                Event::Html(_s) => {}
+                Event::Html(_s) => {
+                    // directives are covered by `suppressed_ranges`
+                }
                 Event::FootnoteReference(_s) => {
                     // @todo handle footnotes
                 }
@@ -145,7 +155,8 @@ impl<'a> PlainOverlay<'a> {
     // @todo consider returning a Vec<PlainOverlay<'a>> to account for list items
     // or other non-linear information which might not pass a grammar check as a whole
     pub fn erase_markdown(chunk: &'a CheckableChunk) -> Self {
-        let (plain, mapping) = Self::extract_plain_with_mapping(chunk.as_str());
+        let (mut plain, mapping) = Self::extract_plain_with_mapping(chunk.as_str());
+        mask_words(&mut plain, chunk.allowed_words());
         Self {
             raw: chunk,
             plain,
@@ -355,6 +366,49 @@ And a line, or a rule."##;
         }
     }
 
+    #[test]
+    fn markdown_suppression() {
+        const MARKDOWN: &str = r#"Checked.
+
+<!-- spellcheck:off -->
+Ignored.
+
+Ignored as well.
+<!-- spellcheck:on -->
+
+Checked again.
+
+<!-- spellcheck:ignore-next-line -->
+Ignored.
+Checked."#;
+        // the paragraph breaks of the suppressed paragraphs are retained
+        const PLAIN: &str = "Checked.\n\n\n\n\n\nChecked again.\n\n\nChecked.";
+
+        let (reduced, mapping) = PlainOverlay::extract_plain_with_mapping(MARKDOWN);
+        assert_eq!(dbg!(&reduced).as_str(), PLAIN);
+        for (reduced_range, markdown_range) in mapping.iter() {
+            assert_eq!(
+                reduced[reduced_range.clone()],
+                MARKDOWN[markdown_range.clone()]
+            );
+        }
+    }
+
+    #[test]
+    fn allowed_words_are_masked() {
+        let mut chunk = CheckableChunk::from_str(
+            "Foobaz is a word.",
+            indexmap::indexmap! { 0..17 => Span {
+                    start: LineColumn { line: 1, column: 0 },
+                    end: LineColumn { line: 1, column: 16 },
+                }
+            },
+        );
+        chunk.allow_words(vec!["Foobaz".to_owned()]);
+        let plain = chunk.erase_markdown();
+        assert_eq!(plain.as_str(), "       is a word.");
+    }
+
     #[test]
     fn range_test() {
         let mut x = IndexMap::<Range, Range>::new();
@@ -14,37 +14,79 @@
 
 use super::*;
 
+use anyhow::Result;
 use indexmap::IndexMap;
-use log::trace;
+use log::{debug, trace};
 pub use proc_macro2::LineColumn;
 use proc_macro2::{Spacing, TokenTree};
-use std::convert::{TryFrom, TryInto};
+use std::collections::BTreeSet;
+use std::convert::TryInto;
 use std::path::PathBuf;
 
 pub type Range = core::ops::Range<usize>;
 
 mod chunk;
 mod cluster;
+mod developer;
+mod directive;
+mod doctest;
 mod literal;
 pub(crate) mod literalset;
+mod manifest;
 mod markdown;
+mod symbols;
 
 pub use chunk::*;
 pub use cluster::*;
+pub use developer::*;
+pub use directive::*;
+pub use doctest::*;
 pub use literal::*;
 pub use literalset::*;
+pub use manifest::*;
 pub use markdown::*;
+pub use symbols::*;
 /// Collection of all the documentation entries across the project
 #[derive(Debug, Clone)]
 pub struct Documentation {
     /// Mapping of a path to documentation literals
     index: IndexMap<ContentOrigin, Vec<CheckableChunk>>,
+    /// Symbols declared or imported by the traversed source files
+    symbols: BTreeSet<String>,
+    /// Paths of the items behind `symbols`, relative to the crate root
+    items: BTreeSet<String>,
 }
 
 impl Documentation {
     pub fn new() -> Self {
         Self {
             index: IndexMap::with_capacity(64),
+            symbols: BTreeSet::new(),
+            items: BTreeSet::new(),
+        }
+    }
+
+    /// The names of all items of the traversed source files.
+    pub fn symbols(&self) -> &BTreeSet<String> {
+        &self.symbols
+    }
+
+    /// The paths of all items of the traversed source files, see `collect_items`.
+    pub fn items(&self) -> &BTreeSet<String> {
+        &self.items
+    }
+
+    /// Add the `items` of a file, which are relative to the module `module`.
+    pub fn add_items(&mut self, module: &str, items: impl IntoIterator<Item = String>) {
+        for item in items {
+            if let Some(name) = item.rsplit("::").next() {
+                self.symbols.insert(name.to_owned());
+            }
+            if module.is_empty() {
+                self.items.insert(item);
+            } else {
+                self.items.insert(format!("{}::{}", module, item));
+            }
         }
     }
 
@@ -61,7 +103,14 @@ impl Documentation {
     }
 
     pub fn join(&mut self, other: Documentation) -> &mut Self {
-        other
+        let Documentation {
+            index,
+            symbols,
+            items,
+        } = other;
+        self.symbols.extend(symbols);
+        self.items.extend(items);
+        index
             .into_iter()
             .for_each(|(source, chunks): (_, Vec<CheckableChunk>)| {
                 let _ = self.add(source, chunks);
@@ -88,24 +137,139 @@ impl Documentation {
             .or_insert_with(|| chunks);
         // Ok(()) @todo make this failable
     }
+
+    /// Add the regular comments of the Rust source `content` besides its doc comments.
+    pub fn add_dev_comments(&mut self, origin: ContentOrigin, content: &str) {
+        let chunks = dev_comment_chunks(content);
+        if !chunks.is_empty() {
+            self.add(origin, chunks);
+        }
+    }
+
+    /// Add the chunks of a file, as well as the chunks of the Rust code blocks within them,
+    /// see `doc_tests`.
+    pub fn add_with_doc_tests(
+        &mut self,
+        source: ContentOrigin,
+        chunks: Vec<CheckableChunk>,
+        dev_comments: bool,
+        string_literals: bool,
+    ) {
+        let doc_tests = chunks
+            .iter()
+            .flat_map(|chunk| doc_tests(&source, chunk, dev_comments, string_literals))
+            .collect::<Vec<_>>();
+        self.add(source, chunks);
+        for (origin, chunks) in doc_tests {
+            self.add(origin, chunks);
+        }
+    }
 }
 
 /// only a shortcut to avoid duplicate code
 impl From<(ContentOrigin, &str)> for Documentation {
     fn from((origin, content): (ContentOrigin, &str)) -> Self {
+        Self::load_rust(origin, content, false, false)
+    }
+}
+
+impl Documentation {
+    /// Load the doc comments of the Rust source `content` and optionally
+    /// its regular comments as well as the string literals of user facing messages.
+    pub fn load_rust(
+        origin: ContentOrigin,
+        content: &str,
+        dev_comments: bool,
+        string_literals: bool,
+    ) -> Self {
         let mut docs = Documentation::new();
 
-        match Clusters::try_from(content) {
+        if let ContentOrigin::RustSourceFile(path) = &origin {
+            match collect_items(content) {
+                Ok(items) => docs.add_items(&module_path(path), items),
+                Err(e) => debug!("Failed to collect the items of {}: {}", &origin, e),
+            }
+        }
+
+        match Clusters::load(content, string_literals) {
             Ok(cluster) => {
                 let chunks = Vec::<CheckableChunk>::from(cluster);
-                docs.add(origin, chunks);
+                docs.add_with_doc_tests(origin.clone(), chunks, dev_comments, string_literals);
             }
             Err(e) => {
                 log::error!("BUG: Failed to create cluster from {}: {}", &origin, e);
             }
         }
+        if dev_comments {
+            docs.add_dev_comments(origin, content);
+        }
+        docs
+    }
+
+    /// Load the common mark `content` as a single chunk, as well as its Rust code blocks,
+    /// optionally including their regular comments and string literals.
+    ///
+    /// Empty content yields no chunks at all.
+    pub fn load_commonmark(
+        origin: ContentOrigin,
+        content: &str,
+        dev_comments: bool,
+        string_literals: bool,
+    ) -> Self {
+        let mut docs = Documentation::new();
+        // extract the full content span and range
+        if let Some((idx, last)) = content.lines().enumerate().last() {
+            let span = Span {
+                start: LineColumn { line: 1, column: 0 },
+                end: LineColumn {
+                    line: idx + 1,
+                    column: last.chars().count(),
+                },
+            };
+            let source_mapping = indexmap::indexmap! {
+                0..content.chars().count() => span
+            };
+            docs.add_with_doc_tests(
+                origin,
+                vec![CheckableChunk::from_str(content, source_mapping)],
+                dev_comments,
+                string_literals,
+            );
+        }
         docs
     }
+
+    /// Load the package metadata of the `Cargo.toml` manifest `content`.
+    pub fn load_manifest(origin: ContentOrigin, content: &str) -> Result<Self> {
+        let mut docs = Documentation::new();
+        docs.add(origin, manifest_chunks(content)?);
+        Ok(docs)
+    }
+
+    /// Load `content` according to the kind of its `origin`, see `load_rust`,
+    /// `load_commonmark` and `load_manifest`.
+    pub fn load(
+        origin: ContentOrigin,
+        content: &str,
+        dev_comments: bool,
+        string_literals: bool,
+    ) -> Result<Self> {
+        match origin {
+            ContentOrigin::CommonMarkFile(_) => Ok(Self::load_commonmark(
+                origin,
+                content,
+                dev_comments,
+                string_literals,
+            )),
+            ContentOrigin::CargoManifest(_) => Self::load_manifest(origin, content),
+            _ => Ok(Self::load_rust(
+                origin,
+                content,
+                dev_comments,
+                string_literals,
+            )),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -199,7 +363,7 @@ mod tests {
             let chunk = &chunks[0];
             let _plain = chunk.erase_markdown();
 
-            let suggestion_set = crate::checker::dummy::DummyChecker::check(&docs, &())
+            let suggestion_set = crate::checker::dummy::DummyChecker::check(&docs, &(), 1, None)
                 .expect("Must not fail to extract suggestions");
             let (_, suggestions) = suggestion_set
                 .iter()
@@ -275,7 +439,8 @@ Erronbeous bold uetchkp"#;
         let docs = Documentation::from((origin.clone(), SOURCE));
 
         // @todo contains utter garbage, should be individual tokens, but is multiple literal
-        let suggestion_set = dbg!(DummyChecker::check(&docs, &())).expect("Must not error");
+        let suggestion_set =
+            dbg!(DummyChecker::check(&docs, &(), 1, None)).expect("Must not error");
         let (origin2, chunks) = docs.iter().next().expect("Must contain exactly one origin");
         assert_eq!(&origin, origin2);
 
@@ -0,0 +1,229 @@
+//! Symbols of a Rust source file
+//!
+//! Collects the paths of all items a file declares, i.e. types, functions,
+//! fields, enum variants and modules, as well as the names it imports with `use`.
+//! Together they form the vocabulary inline code and intra-doc links within the
+//! documentation are expected to refer to.
+
+use std::collections::BTreeSet;
+use std::path::{Component, Path};
+use syn::visit::{self, Visit};
+
+/// Visitor recording the paths of declarations, relative to the file.
+#[derive(Debug, Default)]
+struct ItemCollector {
+    /// The enclosing modules, types or traits of the currently visited node.
+    scope: Vec<String>,
+    items: BTreeSet<String>,
+}
+
+impl ItemCollector {
+    /// Record `ident` within the current scope and return its name.
+    fn record(&mut self, ident: &syn::Ident) -> Option<String> {
+        let ident = ident.to_string();
+        // raw identifiers are referred to without their prefix
+        let ident = ident.strip_prefix("r#").unwrap_or(&ident);
+        // `use foo::{self}` and `use Trait as _` do not introduce a name
+        if matches!(ident, "_" | "self" | "Self" | "super" | "crate") {
+            return None;
+        }
+        let mut path = self.scope.join("::");
+        if !path.is_empty() {
+            path.push_str("::");
+        }
+        path.push_str(ident);
+        self.items.insert(path);
+        Some(ident.to_owned())
+    }
+
+    /// Visit the children of a node within the scope of `name`.
+    fn within(&mut self, name: Option<String>, visit: impl FnOnce(&mut Self)) {
+        match name {
+            Some(name) => {
+                self.scope.push(name);
+                visit(self);
+                self.scope.pop();
+            }
+            None => visit(self),
+        }
+    }
+}
+
+/// Implement the visitor methods of all nodes which declare exactly one identifier.
+///
+/// Nodes marked as `scope` contain further declarations, which are recorded
+/// as paths relative to the node.
+macro_rules! record {
+    ($($method:ident($ty:ty) => |$node:ident| $ident:expr $(, $scope:ident)?;)*) => {
+        $(
+            fn $method(&mut self, $node: &'ast $ty) {
+                let _name = self.record(&$ident);
+                record!(@visit self, _name, $method, $node $(, $scope)?);
+            }
+        )*
+    };
+    (@visit $self:ident, $name:ident, $method:ident, $node:ident) => {
+        visit::$method($self, $node)
+    };
+    (@visit $self:ident, $name:ident, $method:ident, $node:ident, scope) => {
+        $self.within($name, |collector| visit::$method(collector, $node))
+    };
+}
+
+impl<'ast> Visit<'ast> for ItemCollector {
+    record! {
+        visit_item_const(syn::ItemConst) => |node| node.ident;
+        visit_item_enum(syn::ItemEnum) => |node| node.ident, scope;
+        visit_item_fn(syn::ItemFn) => |node| node.sig.ident;
+        visit_item_macro2(syn::ItemMacro2) => |node| node.ident;
+        visit_item_mod(syn::ItemMod) => |node| node.ident, scope;
+        visit_item_static(syn::ItemStatic) => |node| node.ident;
+        visit_item_struct(syn::ItemStruct) => |node| node.ident, scope;
+        visit_item_trait(syn::ItemTrait) => |node| node.ident, scope;
+        visit_item_type(syn::ItemType) => |node| node.ident;
+        visit_item_union(syn::ItemUnion) => |node| node.ident, scope;
+        visit_foreign_item_fn(syn::ForeignItemFn) => |node| node.sig.ident;
+        visit_impl_item_const(syn::ImplItemConst) => |node| node.ident;
+        visit_impl_item_method(syn::ImplItemMethod) => |node| node.sig.ident;
+        visit_impl_item_type(syn::ImplItemType) => |node| node.ident;
+        visit_trait_item_const(syn::TraitItemConst) => |node| node.ident;
+        visit_trait_item_method(syn::TraitItemMethod) => |node| node.sig.ident;
+        visit_trait_item_type(syn::TraitItemType) => |node| node.ident;
+        visit_variant(syn::Variant) => |node| node.ident, scope;
+        visit_use_name(syn::UseName) => |node| node.ident;
+        visit_use_rename(syn::UseRename) => |node| node.rename;
+    }
+
+    fn visit_item_macro(&mut self, node: &'ast syn::ItemMacro) {
+        // `macro_rules! name { .. }`
+        if let Some(ident) = node.ident.as_ref() {
+            self.record(ident);
+        }
+        visit::visit_item_macro(self, node);
+    }
+
+    fn visit_field(&mut self, node: &'ast syn::Field) {
+        // tuple struct fields have no name
+        if let Some(ident) = node.ident.as_ref() {
+            self.record(ident);
+        }
+        visit::visit_field(self, node);
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        // associated items are scoped by the implementing type
+        let name = match node.self_ty.as_ref() {
+            syn::Type::Path(ty) => ty
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string()),
+            _ => None,
+        };
+        self.within(name, |collector| visit::visit_item_impl(collector, node));
+    }
+}
+
+/// Collect the paths, relative to the file, of all items declared or imported by the given Rust source.
+pub fn collect_items(source: &str) -> syn::Result<BTreeSet<String>> {
+    let file = syn::parse_file(source)?;
+    let mut collector = ItemCollector::default();
+    collector.visit_file(&file);
+    Ok(collector.items)
+}
+
+/// The module path of a source file within its crate, i.e. `checker::pool` for `src/checker/pool.rs`.
+///
+/// Derived from the file system layout below the last `src` directory,
+/// crate roots and `mod.rs` files do not add a segment.
+pub fn module_path(path: &Path) -> String {
+    let components = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let below_src = components
+        .iter()
+        .rposition(|name| *name == "src")
+        .map(|idx| &components[idx + 1..])
+        .unwrap_or_else(|| &components[components.len().saturating_sub(1)..]);
+    below_src
+        .iter()
+        .map(|name| name.strip_suffix(".rs").unwrap_or(name))
+        .filter(|name| !matches!(*name, "lib" | "main" | "mod"))
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declarations() {
+        const SOURCE: &str = r#"
+use std::collections::HashMap;
+use std::io::{self, Read as _, Write as W};
+
+pub mod nested {
+    pub struct Point { pub x: f32, r#type: u8 }
+    pub struct Tuple(u8);
+}
+
+enum Shape { Circle, Square }
+
+trait Area {
+    const SIDES: usize;
+    fn area(&self) -> f32;
+}
+
+impl Area for Shape {
+    const SIDES: usize = 0;
+    fn area(&self) -> f32 { let local = 1.0; local }
+}
+
+macro_rules! shout { () => {} }
+
+fn main() {}
+"#;
+        let items = collect_items(SOURCE).unwrap();
+        assert_eq!(
+            items.into_iter().collect::<Vec<_>>(),
+            vec![
+                "Area",
+                "Area::SIDES",
+                "Area::area",
+                "HashMap",
+                "Shape",
+                "Shape::Circle",
+                "Shape::SIDES",
+                "Shape::Square",
+                "Shape::area",
+                "W",
+                "main",
+                "nested",
+                "nested::Point",
+                "nested::Point::type",
+                "nested::Point::x",
+                "nested::Tuple",
+                "shout",
+            ]
+        );
+    }
+
+    #[test]
+    fn module_paths() {
+        assert_eq!(module_path(Path::new("/repo/src/lib.rs")), "");
+        assert_eq!(
+            module_path(Path::new("/repo/src/checker/mod.rs")),
+            "checker"
+        );
+        assert_eq!(
+            module_path(Path::new("/repo/src/checker/pool.rs")),
+            "checker::pool"
+        );
+        assert_eq!(module_path(Path::new("/tmp/dummy.rs")), "dummy");
+    }
+}
@@ -0,0 +1,156 @@
+//! Spellcheck all your doc comments
+//!
+//! Besides the `cargo-spellcheck` binary, the extraction and checking is
+//! available as library, so other linters can reuse it.
+//!
+//! ```no_run
+//! use cargo_spellcheck::{Config, ContentOrigin, Spellcheck};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let spellcheck = Spellcheck::new(Config::default()).jobs(2);
+//! let documentation = spellcheck.load_str(
+//!     ContentOrigin::RustSourceFile("src/lib.rs".into()),
+//!     "/// A documentd function.\nfn foo() {}\n",
+//! )?;
+//! for (origin, suggestions) in spellcheck.check(&documentation)?.iter() {
+//!     for suggestion in suggestions {
+//!         println!("{}: {:?}", origin, suggestion.replacements);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod action;
+pub mod checker;
+pub mod config;
+pub mod documentation;
+pub mod lsp;
+pub mod output;
+mod span;
+mod suggestion;
+pub mod traverse;
+mod util;
+
+pub use self::action::*;
+pub use self::checker::Checker;
+pub use self::config::{Config, HunspellConfig, LanguageToolConfig};
+pub use self::documentation::*;
+pub use self::output::*;
+pub use self::span::*;
+pub use self::suggestion::*;
+pub use self::util::*;
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Extract and check content with a given configuration.
+///
+/// The suggestions refer to the chunks of the `Documentation` they were found in,
+/// so loading and checking are separate steps.
+#[derive(Debug, Clone)]
+pub struct Spellcheck {
+    config: Config,
+    jobs: usize,
+    cache_dir: Option<PathBuf>,
+    skip_readme: bool,
+}
+
+impl Spellcheck {
+    /// Check with a single worker per checker and without a cache.
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            jobs: 1,
+            cache_dir: None,
+            skip_readme: false,
+        }
+    }
+
+    /// Number of files checked concurrently.
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    /// Reuse the results of previous runs stored in `dir`, see `checker::cache::default_dir`.
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Do not check the `README.md` files and package metadata of manifests.
+    pub fn skip_readme(mut self, skip_readme: bool) -> Self {
+        self.skip_readme = skip_readme;
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Extract the documentation of a single file's `content`, which is interpreted
+    /// according to the kind of the `origin`.
+    pub fn load_str(&self, origin: ContentOrigin, content: &str) -> Result<Documentation> {
+        Documentation::load(
+            origin,
+            content,
+            self.config.dev_comments,
+            self.config.string_literals,
+        )
+    }
+
+    /// Extract the documentation of the given files, directories or manifests,
+    /// optionally following the module declarations of the source files.
+    ///
+    /// No paths at all check the project in the current working directory.
+    pub fn load_paths(&self, paths: Vec<PathBuf>, recursive: bool) -> Result<Documentation> {
+        traverse::extract(paths, recursive, self.skip_readme, &self.config)
+    }
+
+    /// Run all enabled checkers on the `documentation`.
+    pub fn check<'a>(&self, documentation: &'a Documentation) -> Result<SuggestionSet<'a>> {
+        checker::check(
+            documentation,
+            &self.config,
+            self.jobs,
+            self.cache_dir.as_deref().map(Path::new),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SymbolsConfig;
+
+    #[test]
+    fn check_str() {
+        let config = Config {
+            hunspell: None,
+            languagetool: None,
+            symbols: Some(SymbolsConfig::default()),
+            links: None,
+            ..Default::default()
+        };
+        let spellcheck = Spellcheck::new(config).jobs(2);
+        let documentation = spellcheck
+            .load_str(
+                ContentOrigin::RustSourceFile("src/registry.rs".into()),
+                "use std::collections::HashMap;\n/// Wraps a `HashMpa`.\nstruct Registry(HashMap<u8, u8>);\n",
+            )
+            .unwrap();
+        let suggestions = spellcheck.check(&documentation).unwrap();
+        assert_eq!(suggestions.total_count(), 1);
+        let (_origin, suggestions) = suggestions.iter().next().unwrap();
+        assert_eq!(suggestions[0].replacements, vec!["HashMap".to_owned()]);
+
+        let documentation = spellcheck
+            .load_str(
+                ContentOrigin::CommonMarkFile("README.md".into()),
+                "# Registry\n\nWraps a `HashMap`.\n",
+            )
+            .unwrap();
+        assert_eq!(documentation.iter().count(), 1);
+    }
+}
@@ -0,0 +1,767 @@
+//! Language server for in-editor spellchecking
+//!
+//! Speaks the language server protocol over stdio, see
+//! <https://microsoft.github.io/language-server-protocol/specification>.
+//!
+//! Every open buffer is extracted into its own `Documentation`, which is rebuilt
+//! and checked again whenever the buffer changes. The findings are published as
+//! diagnostics, their replacements are offered as quick fixes, and spelling
+//! mistakes can be added to the `project_wordlist` of the `[Hunspell]` or `[Native]`
+//! configuration.
+
+use crate::checker;
+use crate::documentation::{ContentOrigin, Documentation};
+use crate::util::sub_chars;
+use crate::{Config, Detector, Span, Suggestion};
+
+use anyhow::{anyhow, bail, Result};
+use indexmap::IndexMap;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use url::Url;
+
+mod transport;
+
+use self::transport::{read_message, write_message};
+
+/// Command adding the word passed as single argument to the project word list.
+pub const ADD_TO_DICTIONARY: &str = "cargo-spellcheck.addToDictionary";
+
+/// Name of the server, as reported to the client and as source of the diagnostics.
+const SERVER_NAME: &str = "cargo-spellcheck";
+
+/// JSON-RPC error codes.
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Position within a buffer, the `character` counts UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct Position {
+    line: usize,
+    character: usize,
+}
+
+/// Range within a buffer, the `end` is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Range {
+    start: Position,
+    end: Position,
+}
+
+impl Range {
+    fn overlaps(&self, other: &Range) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    id: Option<Value>,
+    method: Option<String>,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TextDocumentItem {
+    uri: Url,
+    #[serde(default)]
+    language_id: String,
+    version: Option<i64>,
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextDocumentIdentifier {
+    uri: Url,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionedTextDocumentIdentifier {
+    uri: Url,
+    version: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextDocumentContentChangeEvent {
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DidOpenTextDocumentParams {
+    #[serde(rename = "textDocument")]
+    text_document: TextDocumentItem,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidChangeTextDocumentParams {
+    text_document: VersionedTextDocumentIdentifier,
+    content_changes: Vec<TextDocumentContentChangeEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidCloseTextDocumentParams {
+    text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CodeActionParams {
+    text_document: TextDocumentIdentifier,
+    range: Range,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExecuteCommandParams {
+    command: String,
+    #[serde(default)]
+    arguments: Vec<Value>,
+}
+
+/// A finding within a buffer, detached from the `Documentation` it was found in.
+#[derive(Debug, Clone)]
+struct Finding {
+    detector: Detector,
+    range: Range,
+    word: String,
+    description: Option<String>,
+    replacements: Vec<String>,
+}
+
+impl Finding {
+    fn diagnostic(&self) -> Value {
+        let message = if let Some(ref description) = self.description {
+            format!("{} `{}`", description, self.word)
+        } else {
+            format!("Possible mistake `{}`", self.word)
+        };
+        json!({
+            "range": self.range,
+            "severity": 2,
+            "source": SERVER_NAME,
+            "code": self.detector.to_string(),
+            "message": message,
+        })
+    }
+}
+
+/// An open buffer, with the documentation extracted from its latest content.
+struct Buffer {
+    version: Option<i64>,
+    /// As announced when opened, changes do not carry it.
+    language_id: String,
+    text: String,
+    documentation: Documentation,
+    findings: Vec<Finding>,
+}
+
+/// The number of UTF-16 code units of the first `column` chars of `line`.
+fn utf16_column(line: &str, column: usize) -> usize {
+    line.chars().take(column).map(char::len_utf16).sum()
+}
+
+/// Convert the inclusive `span` into a protocol range within `text`.
+fn range_of(text: &str, span: Span) -> Range {
+    let line = |lineno: usize| text.lines().nth(lineno.saturating_sub(1)).unwrap_or("");
+    Range {
+        start: Position {
+            line: span.start.line.saturating_sub(1),
+            character: utf16_column(line(span.start.line), span.start.column),
+        },
+        end: Position {
+            line: span.end.line.saturating_sub(1),
+            character: utf16_column(line(span.end.line), span.end.column + 1),
+        },
+    }
+}
+
+/// Language server holding the open buffers.
+pub struct Server {
+    config: Config,
+    jobs: usize,
+    buffers: IndexMap<Url, Buffer>,
+    shutdown: bool,
+}
+
+impl Server {
+    pub fn new(config: Config, jobs: usize) -> Self {
+        Self {
+            config,
+            jobs,
+            buffers: IndexMap::new(),
+            shutdown: false,
+        }
+    }
+
+    /// Serve the client until it sends `exit`.
+    ///
+    /// Fails if the client exits without requesting a `shutdown` first.
+    pub fn serve<R: BufRead, W: Write>(mut self, mut reader: R, mut writer: W) -> Result<()> {
+        info!("Serving language server protocol requests");
+        while let Some(message) = read_message(&mut reader)? {
+            let message: Message = match serde_json::from_value(message) {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!("Ignoring malformed message: {}", e);
+                    continue;
+                }
+            };
+            let method = match message.method {
+                Some(method) => method,
+                // a response to a request, the server does not send any
+                None => continue,
+            };
+            if method == "exit" {
+                if self.shutdown {
+                    return Ok(());
+                }
+                bail!("Client exited without a prior shutdown request");
+            }
+            match message.id {
+                Some(id) => {
+                    let response = match self.request(&method, message.params, &mut writer) {
+                        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                        Err((code, message)) => json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": {"code": code, "message": message},
+                        }),
+                    };
+                    write_message(&mut writer, &response)?;
+                }
+                None => {
+                    if let Err(e) = self.notification(&method, message.params, &mut writer) {
+                        warn!("Failed to handle notification `{}`: {:#}", method, e);
+                    }
+                }
+            }
+        }
+        bail!("Client closed the connection without exiting")
+    }
+
+    /// Handle a request, the error is a JSON-RPC error code and message.
+    fn request<W: Write>(
+        &mut self,
+        method: &str,
+        params: Value,
+        writer: &mut W,
+    ) -> std::result::Result<Value, (i64, String)> {
+        if self.shutdown {
+            return Err((INVALID_REQUEST, "Server is shutting down".to_owned()));
+        }
+        let invalid = |e: serde_json::Error| (INVALID_PARAMS, e.to_string());
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    // full content on every change
+                    "textDocumentSync": 1,
+                    "codeActionProvider": true,
+                    "executeCommandProvider": {
+                        "commands": [ADD_TO_DICTIONARY],
+                    },
+                },
+                "serverInfo": {
+                    "name": SERVER_NAME,
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/codeAction" => {
+                let params: CodeActionParams = serde_json::from_value(params).map_err(invalid)?;
+                Ok(self.code_actions(&params.text_document.uri, params.range))
+            }
+            "workspace/executeCommand" => {
+                let params: ExecuteCommandParams =
+                    serde_json::from_value(params).map_err(invalid)?;
+                let word = match (params.command.as_str(), params.arguments.as_slice()) {
+                    (ADD_TO_DICTIONARY, [Value::String(word)]) => word.clone(),
+                    (ADD_TO_DICTIONARY, _) => {
+                        return Err((
+                            INVALID_PARAMS,
+                            format!("`{}` expects a single word", ADD_TO_DICTIONARY),
+                        ))
+                    }
+                    (command, _) => {
+                        return Err((INVALID_PARAMS, format!("Unknown command `{}`", command)))
+                    }
+                };
+                self.add_to_dictionary(&word, writer)
+                    .map(|_| Value::Null)
+                    .map_err(|e| (INTERNAL_ERROR, format!("{:#}", e)))
+            }
+            method => Err((METHOD_NOT_FOUND, format!("Unsupported method `{}`", method))),
+        }
+    }
+
+    /// Handle a notification, unknown ones are ignored.
+    fn notification<W: Write>(
+        &mut self,
+        method: &str,
+        params: Value,
+        writer: &mut W,
+    ) -> Result<()> {
+        match method {
+            "textDocument/didOpen" => {
+                let params: DidOpenTextDocumentParams = serde_json::from_value(params)?;
+                let item = params.text_document;
+                let documentation = self.extract(&item.uri, &item.language_id, &item.text);
+                self.buffers.insert(
+                    item.uri.clone(),
+                    Buffer {
+                        version: item.version,
+                        language_id: item.language_id,
+                        text: item.text,
+                        documentation,
+                        findings: Vec::new(),
+                    },
+                );
+                self.check(&item.uri, writer)?;
+            }
+            "textDocument/didChange" => {
+                let params: DidChangeTextDocumentParams = serde_json::from_value(params)?;
+                let uri = params.text_document.uri;
+                // with full synchronization the last change carries the whole content
+                let text = match params.content_changes.into_iter().last() {
+                    Some(change) => change.text,
+                    None => return Ok(()),
+                };
+                let documentation = match self.buffers.get(&uri) {
+                    Some(buffer) => self.extract(&uri, &buffer.language_id, &text),
+                    None => {
+                        warn!("Received a change of {}, which is not open", uri);
+                        return Ok(());
+                    }
+                };
+                if let Some(buffer) = self.buffers.get_mut(&uri) {
+                    buffer.version = params.text_document.version;
+                    buffer.text = text;
+                    buffer.documentation = documentation;
+                }
+                self.check(&uri, writer)?;
+            }
+            "textDocument/didClose" => {
+                let params: DidCloseTextDocumentParams = serde_json::from_value(params)?;
+                let uri = params.text_document.uri;
+                if self.buffers.remove(&uri).is_some() {
+                    publish(writer, &uri, None, &[])?;
+                }
+            }
+            method => debug!("Ignoring notification `{}`", method),
+        }
+        Ok(())
+    }
+
+    /// Extract the documentation of a buffer, based on the language or the file extension.
+    fn extract(&self, uri: &Url, language_id: &str, text: &str) -> Documentation {
+        let path = uri
+            .to_file_path()
+            .unwrap_or_else(|_| PathBuf::from(uri.path()));
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or("");
+        let origin = if language_id == "rust" || extension == "rs" {
+            ContentOrigin::RustSourceFile(path)
+        } else if language_id == "markdown" || extension == "md" {
+            ContentOrigin::CommonMarkFile(path)
+        } else if path.file_name().map(|name| name == "Cargo.toml") == Some(true) {
+            ContentOrigin::CargoManifest(path)
+        } else {
+            debug!("Not checking {}, which is neither Rust nor markdown", uri);
+            return Documentation::new();
+        };
+        Documentation::load(
+            origin,
+            text,
+            self.config.dev_comments,
+            self.config.string_literals,
+        )
+        .unwrap_or_else(|e| {
+            debug!("Failed to extract {}: {}", uri, e);
+            Documentation::new()
+        })
+    }
+
+    /// Check the documentation of the buffer `uri` and publish the findings.
+    fn check<W: Write>(&mut self, uri: &Url, writer: &mut W) -> Result<()> {
+        let buffer = match self.buffers.get_mut(uri) {
+            Some(buffer) => buffer,
+            None => return Ok(()),
+        };
+        let findings = match checker::check(&buffer.documentation, &self.config, self.jobs, None) {
+            Ok(suggestions) => suggestions
+                .iter()
+                .flat_map(|(_origin, suggestions)| suggestions.iter())
+                .map(|suggestion: &Suggestion| Finding {
+                    detector: suggestion.detector,
+                    range: range_of(&buffer.text, suggestion.span),
+                    word: sub_chars(suggestion.chunk.as_str(), suggestion.range.clone()),
+                    description: suggestion.description.clone(),
+                    replacements: suggestion.replacements.clone(),
+                })
+                .collect(),
+            Err(e) => {
+                show_error(writer, &format!("Failed to check {}: {:#}", uri, e))?;
+                Vec::new()
+            }
+        };
+        buffer.findings = findings;
+        publish(writer, uri, buffer.version, &buffer.findings)
+    }
+
+    /// Quick fixes for all findings overlapping `range`.
+    fn code_actions(&self, uri: &Url, range: Range) -> Value {
+        let buffer = match self.buffers.get(uri) {
+            Some(buffer) => buffer,
+            None => return json!([]),
+        };
+        let mut actions = Vec::new();
+        for finding in buffer
+            .findings
+            .iter()
+            .filter(|finding| finding.range.overlaps(&range))
+        {
+            let diagnostic = finding.diagnostic();
+            for replacement in finding.replacements.iter() {
+                actions.push(json!({
+                    "title": format!("Replace with `{}`", replacement),
+                    "kind": "quickfix",
+                    "diagnostics": [diagnostic],
+                    "edit": {
+                        "changes": {
+                            uri.as_str(): [{"range": finding.range, "newText": replacement}],
+                        },
+                    },
+                }));
+            }
+            if finding.detector == Detector::Hunspell || finding.detector == Detector::Native {
+                let title = format!("Add `{}` to the project word list", finding.word);
+                actions.push(json!({
+                    "title": title,
+                    "kind": "quickfix",
+                    "diagnostics": [diagnostic],
+                    "command": {
+                        "title": title,
+                        "command": ADD_TO_DICTIONARY,
+                        "arguments": [finding.word],
+                    },
+                }));
+            }
+        }
+        Value::Array(actions)
+    }
+
+    /// Add `word` to the project word list, all buffers are checked again afterwards.
+    fn add_to_dictionary<W: Write>(&mut self, word: &str, writer: &mut W) -> Result<()> {
+        let project_wordlist = self.config.project_wordlist().ok_or_else(|| {
+            anyhow!(
+                "No `project_wordlist` configured, `{}` is not remembered",
+                word
+            )
+        })?;
+        checker::wordlist::append(project_wordlist, word)?;
+        info!("Added `{}` to {}", word, project_wordlist.display());
+
+        let uris = self.buffers.keys().cloned().collect::<Vec<_>>();
+        for uri in uris {
+            self.check(&uri, writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Publish the diagnostics of the buffer `uri`, replacing all previous ones.
+fn publish<W: Write>(
+    writer: &mut W,
+    uri: &Url,
+    version: Option<i64>,
+    findings: &[Finding],
+) -> Result<()> {
+    let diagnostics = findings.iter().map(Finding::diagnostic).collect::<Vec<_>>();
+    write_message(
+        writer,
+        &json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": uri,
+                "version": version,
+                "diagnostics": diagnostics,
+            },
+        }),
+    )
+}
+
+/// Show an error to the user, the server keeps running.
+fn show_error<W: Write>(writer: &mut W, message: &str) -> Result<()> {
+    warn!("{}", message);
+    write_message(
+        writer,
+        &json!({
+            "jsonrpc": "2.0",
+            "method": "window/showMessage",
+            "params": {"type": 1, "message": message},
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SymbolsConfig;
+    use crate::LineColumn;
+    use std::io::Cursor;
+
+    const SOURCE: &str = "use std::collections::HashMap;\n\n/// Wraps a `HashMpa`.\npub struct Registry(HashMap<String, usize>);\n";
+
+    fn config() -> Config {
+        Config {
+            hunspell: None,
+            languagetool: None,
+            symbols: Some(SymbolsConfig::default()),
+            links: None,
+            ..Default::default()
+        }
+    }
+
+    /// Run a scripted session and collect everything the server sent.
+    fn session(config: Config, script: &[Value]) -> Vec<Value> {
+        let mut input = Vec::new();
+        for message in script {
+            write_message(&mut input, message).unwrap();
+        }
+        let mut output = Vec::new();
+        Server::new(config, 1)
+            .serve(Cursor::new(input), &mut output)
+            .expect("Session ends with shutdown and exit");
+
+        let mut reader = Cursor::new(output);
+        let mut received = Vec::new();
+        while let Some(message) = read_message(&mut reader).unwrap() {
+            received.push(message);
+        }
+        received
+    }
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+    }
+
+    fn notification(method: &str, params: Value) -> Value {
+        json!({"jsonrpc": "2.0", "method": method, "params": params})
+    }
+
+    #[test]
+    fn utf16_ranges() {
+        let text = "/// ü😀 `HashMpa`\n";
+        let span = Span {
+            start: LineColumn { line: 1, column: 8 },
+            end: LineColumn {
+                line: 1,
+                column: 14,
+            },
+        };
+        let range = range_of(text, span);
+        assert_eq!(
+            range.start,
+            Position {
+                line: 0,
+                character: 9
+            }
+        );
+        assert_eq!(
+            range.end,
+            Position {
+                line: 0,
+                character: 16
+            }
+        );
+    }
+
+    #[test]
+    fn diagnostics_and_quick_fixes() {
+        let uri = "file:///tmp/lsp/registry.rs";
+        let fixed = SOURCE.replace("HashMpa", "HashMap");
+        let received = session(
+            config(),
+            &[
+                request(1, "initialize", json!({"capabilities": {}})),
+                notification("initialized", json!({})),
+                notification(
+                    "textDocument/didOpen",
+                    json!({"textDocument": {
+                        "uri": uri, "languageId": "rust", "version": 1, "text": SOURCE,
+                    }}),
+                ),
+                request(
+                    2,
+                    "textDocument/codeAction",
+                    json!({
+                        "textDocument": {"uri": uri},
+                        "range": {
+                            "start": {"line": 2, "character": 14},
+                            "end": {"line": 2, "character": 14},
+                        },
+                        "context": {"diagnostics": []},
+                    }),
+                ),
+                notification(
+                    "textDocument/didChange",
+                    json!({
+                        "textDocument": {"uri": uri, "version": 2},
+                        "contentChanges": [{"text": fixed}],
+                    }),
+                ),
+                notification(
+                    "textDocument/didClose",
+                    json!({"textDocument": {"uri": uri}}),
+                ),
+                request(3, "textDocument/hover", json!({})),
+                request(4, "shutdown", Value::Null),
+                notification("exit", Value::Null),
+            ],
+        );
+        assert_eq!(received.len(), 7);
+
+        assert_eq!(received[0]["id"], 1);
+        let capabilities = &received[0]["result"]["capabilities"];
+        assert_eq!(capabilities["textDocumentSync"], 1);
+        assert_eq!(
+            capabilities["executeCommandProvider"]["commands"],
+            json!([ADD_TO_DICTIONARY])
+        );
+
+        assert_eq!(received[1]["method"], "textDocument/publishDiagnostics");
+        assert_eq!(received[1]["params"]["version"], 1);
+        let diagnostics = received[1]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0]["range"],
+            json!({
+                "start": {"line": 2, "character": 13},
+                "end": {"line": 2, "character": 20},
+            })
+        );
+        assert_eq!(diagnostics[0]["code"], "Symbols");
+
+        assert_eq!(received[2]["id"], 2);
+        let actions = received[2]["result"].as_array().unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0]["title"], "Replace with `HashMap`");
+        assert_eq!(
+            actions[0]["edit"]["changes"][uri][0]["newText"],
+            json!("HashMap")
+        );
+
+        // fixing the typo clears the diagnostics, so does closing the buffer
+        assert_eq!(received[3]["params"]["version"], 2);
+        assert_eq!(received[3]["params"]["diagnostics"], json!([]));
+        assert_eq!(received[4]["params"]["diagnostics"], json!([]));
+
+        assert_eq!(received[5]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(
+            received[6],
+            json!({"jsonrpc": "2.0", "id": 4, "result": null})
+        );
+    }
+
+    #[test]
+    fn untitled_buffer() {
+        let uri = "untitled:Untitled-1";
+        let changed = SOURCE.replace("Wraps", "Still wraps");
+        let received = session(
+            config(),
+            &[
+                request(1, "initialize", json!({"capabilities": {}})),
+                notification(
+                    "textDocument/didOpen",
+                    json!({"textDocument": {
+                        "uri": uri, "languageId": "rust", "version": 1, "text": SOURCE,
+                    }}),
+                ),
+                notification(
+                    "textDocument/didChange",
+                    json!({
+                        "textDocument": {"uri": uri, "version": 2},
+                        "contentChanges": [{"text": changed}],
+                    }),
+                ),
+                request(2, "shutdown", Value::Null),
+                notification("exit", Value::Null),
+            ],
+        );
+        assert_eq!(received.len(), 4);
+        // without an extension, the language of the opened buffer is retained
+        for (published, version) in received[1..3].iter().zip(1..) {
+            assert_eq!(published["params"]["version"], version);
+            assert_eq!(
+                published["params"]["diagnostics"].as_array().unwrap().len(),
+                1
+            );
+        }
+    }
+
+    #[test]
+    fn add_to_dictionary() {
+        let path = std::env::temp_dir()
+            .join(format!("cargo-spellcheck-lsp-{}", std::process::id()))
+            .join("wordlist.txt");
+        let _ = std::fs::remove_file(&path);
+
+        let mut config = config();
+        let mut hunspell = Config::default()
+            .hunspell
+            .expect("Hunspell is configured by default");
+        hunspell.project_wordlist = Some(path.clone());
+        // do not run hunspell itself, no buffer is open anyway
+        config.hunspell = Some(hunspell);
+
+        let received = session(
+            config,
+            &[
+                request(1, "initialize", json!({"capabilities": {}})),
+                request(
+                    2,
+                    "workspace/executeCommand",
+                    json!({"command": ADD_TO_DICTIONARY, "arguments": ["bandaid"]}),
+                ),
+                request(
+                    3,
+                    "workspace/executeCommand",
+                    json!({"command": ADD_TO_DICTIONARY, "arguments": []}),
+                ),
+                request(4, "shutdown", Value::Null),
+                notification("exit", Value::Null),
+            ],
+        );
+        assert_eq!(
+            received[1],
+            json!({"jsonrpc": "2.0", "id": 2, "result": null})
+        );
+        assert_eq!(received[2]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(
+            checker::wordlist::load(&path).unwrap(),
+            vec!["bandaid".to_owned()]
+        );
+    }
+
+    #[test]
+    fn exit_without_shutdown() {
+        let mut input = Vec::new();
+        write_message(&mut input, &notification("exit", Value::Null)).unwrap();
+        assert!(Server::new(config(), 1)
+            .serve(Cursor::new(input), Vec::new())
+            .is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! Base protocol framing
+//!
+//! Every message is a JSON-RPC object, preceded by a header with its length in bytes.
+//!
+//! ```text
+//! Content-Length: 52\r\n
+//! \r\n
+//! {"jsonrpc":"2.0","id":1,"method":"shutdown"}
+//! ```
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+use std::io::{BufRead, Write};
+
+/// Read the next message, `None` if the stream ended before a new message started.
+pub(crate) fn read_message<R: BufRead>(reader: &mut R) -> Result<Option<Value>> {
+    let mut content_length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let n = reader
+            .read_line(&mut line)
+            .map_err(|e| anyhow!("Failed to read message header").context(e))?;
+        if n == 0 {
+            if content_length.is_none() {
+                return Ok(None);
+            }
+            bail!("Stream ended within a message header");
+        }
+        let line = line.trim_end_matches(&['\r', '\n'][..]);
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Malformed header line `{}`", line))?;
+        if name.trim().eq_ignore_ascii_case("Content-Length") {
+            let value = value.trim();
+            content_length = Some(
+                value
+                    .parse::<usize>()
+                    .map_err(|e| anyhow!("Invalid content length `{}`", value).context(e))?,
+            );
+        }
+        // `Content-Type` is optional and always `utf-8` encoded JSON
+    }
+    let content_length =
+        content_length.ok_or_else(|| anyhow!("Message header lacks a `Content-Length`"))?;
+    let mut content = vec![0u8; content_length];
+    reader
+        .read_exact(&mut content)
+        .map_err(|e| anyhow!("Failed to read message content").context(e))?;
+    let message = serde_json::from_slice(&content)
+        .map_err(|e| anyhow!("Message content is not valid JSON").context(e))?;
+    Ok(Some(message))
+}
+
+/// Write a single message including its header and flush it.
+pub(crate) fn write_message<W: Write>(writer: &mut W, message: &Value) -> Result<()> {
+    let content = serde_json::to_string(message)?;
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )
+    .map_err(|e| anyhow!("Failed to write message").context(e))?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Cursor;
+
+    #[test]
+    fn roundtrip() {
+        let mut sink = Vec::new();
+        let first = json!({"jsonrpc": "2.0", "id": 1, "method": "shutdown"});
+        let second = json!({"jsonrpc": "2.0", "method": "exit", "params": {"ü": "ß"}});
+        write_message(&mut sink, &first).unwrap();
+        write_message(&mut sink, &second).unwrap();
+
+        let mut reader = Cursor::new(sink);
+        assert_eq!(read_message(&mut reader).unwrap(), Some(first));
+        assert_eq!(read_message(&mut reader).unwrap(), Some(second));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn missing_length() {
+        let mut reader = Cursor::new(b"Content-Type: application/json\r\n\r\n{}".to_vec());
+        assert!(read_message(&mut reader).is_err());
+    }
+}
@@ -1,20 +1,10 @@
-mod action;
-mod checker;
-mod config;
-mod documentation;
-mod span;
-mod suggestion;
-mod traverse;
-mod util;
-
-pub use self::action::*;
-pub use self::config::{Config, HunspellConfig, LanguageToolConfig};
-pub use self::documentation::*;
-pub use self::span::*;
-pub use self::suggestion::*;
-pub use self::util::*;
+use cargo_spellcheck::{
+    action, checker, config, lsp, traverse, Action, Baseline, Config, Destination, Detector,
+    Finish, OutputFormat, SuggestionSet,
+};
 
 use docopt::Docopt;
+use enumflags2::BitFlags;
 
 use log::{debug, info, trace, warn};
 use serde::Deserialize;
@@ -22,27 +12,47 @@ use serde::Deserialize;
 #[cfg(not(target_os = "windows"))]
 use signal_hook::{iterator, SIGINT, SIGQUIT, SIGTERM};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const USAGE: &str = r#"
 Spellcheck all your doc comments
 
 Usage:
-    cargo-spellcheck [(-v...|-q)] check [--cfg=<cfg>] [--code=<code>] [--skip-readme] [--checkers=<checkers>] [[--recursive] <paths>... ]
-    cargo-spellcheck [(-v...|-q)] fix [--cfg=<cfg>] [--code=<code>] [--skip-readme] [--checkers=<checkers>] [[--recursive] <paths>... ]
+    cargo-spellcheck [(-v...|-q)] check [--cfg=<cfg>] [--code=<code>] [--skip-readme] [--checkers=<checkers>] [--jobs=<jobs>] [--no-cache] [--changed-since=<rev>] [--format=<format>] [--baseline=<baseline>] [[--recursive] <paths>... ]
+    cargo-spellcheck [(-v...|-q)] fix [--cfg=<cfg>] [--code=<code>] [--skip-readme] [--checkers=<checkers>] [--jobs=<jobs>] [--no-cache] [--changed-since=<rev>] [--auto] [--dry-run] [--allow=<detectors>] [--patch=<patch>] [[--recursive] <paths>... ]
+    cargo-spellcheck [(-v...|-q)] baseline [--cfg=<cfg>] [--skip-readme] [--checkers=<checkers>] [--jobs=<jobs>] [--no-cache] [--baseline=<baseline>] [[--recursive] <paths>... ]
+    cargo-spellcheck [(-v...|-q)] reflow [--cfg=<cfg>] [--code=<code>] [--skip-readme] [--patch=<patch>] [[--recursive] <paths>... ]
+    cargo-spellcheck [(-v...|-q)] lsp [--cfg=<cfg>] [--checkers=<checkers>] [--jobs=<jobs>]
     cargo-spellcheck [(-v...|-q)] config (--user|--stdout|--cfg=<cfg>) [--force]
-    cargo-spellcheck [(-v...|-q)] [--cfg=<cfg>] [--fix] [--code=<code>] [--skip-readme] [--checkers=<checkers>] [[--recursive] <paths>... ]
+    cargo-spellcheck [(-v...|-q)] [--cfg=<cfg>] [--fix] [--patch=<patch>] [--code=<code>] [--skip-readme] [--checkers=<checkers>] [--jobs=<jobs>] [--no-cache] [--changed-since=<rev>] [--format=<format>] [--baseline=<baseline>] [[--recursive] <paths>... ]
     cargo-spellcheck --help
     cargo-spellcheck --version
 
+Commands:
+  reflow                    Re-wrap the paragraphs of doc comments to the `max_line_length` of the
+                            `[Reflow]` configuration. With `--patch` the result is written as unified
+                            diff instead of modifying the files.
+
 Options:
   -h --help                 Show this screen.
   --version                 Print the version and exit.
 
   --fix                     Interactively apply spelling and grammer fixes, synonym to `fix` sub-command.
+  --auto                    Apply the first suggested replacement without asking, for use in scripts.
+  --dry-run                 Only print the replacements that would be applied, do not modify any files.
+                            Requires `--auto`.
+  --allow=<detectors>       Comma separated list of detectors whose suggestions may be applied automatically,
+                            i.e. `hunspell,languagetool`. Defaults to all. Requires `--auto`.
+  --patch=<patch>           Write the fixes as unified diff to the given file, `-` for stdout,
+                            instead of modifying the files. The result can be applied with `git apply`.
   -r --recursive            If a path is provided, if recursion into subdirectories is desired.
   --checkers=<checkers>     Calculate the intersection between
                             configured by config file and the ones provided on commandline.
+  -j --jobs=<jobs>          Number of files checked concurrently. Defaults to the number of CPUs.
+  --no-cache                Check all chunks again, instead of reusing the results of previous runs
+                            stored in `target/spellcheck`.
+  --changed-since=<rev>     Only check `.rs` and `.md` files changed relative to the given git revision
+                            and only report findings on the changed lines, i.e. `HEAD` in pre-commit hooks.
   -f --force                Overwrite any existing configuration file. [default=false]
   -c --cfg=<cfg>            Use a non default configuration file.
                             Passing a directory will attempt to open `cargo_spellcheck.toml` in that directory.
@@ -51,9 +61,19 @@ Options:
   -v --verbose              Verbosity level.
   -q --quiet                Silences all printed messages. Overrules `-v`.
   -m --code=<code>          Overwrite the exit value for a successful run with content mistakes found. [default=0]
-  --skip-readme             Do not attempt to process README.md files listed in Cargo.toml manifests.
+  --skip-readme             Do not attempt to process README.md files listed in Cargo.toml manifests,
+                            nor the package description, keywords and categories of the manifests.
+  --format=<format>         Output format of the `check` findings, one of `human`, `short`, `json`, `ndjson`,
+                            `sarif`, `github` (Actions annotations) or `gitlab` (Code Quality report).
+                            The `json` and `ndjson` formats follow a versioned schema. [default: human]
+  --baseline=<baseline>     Baseline file of accepted findings. The `baseline` sub-command records all current
+                            findings into it, `check` then only reports findings which are not part of it.
+                            Defaults to `spellcheck-baseline.json` for the `baseline` sub-command.
 "#;
 
+/// Baseline file used by the `baseline` sub-command if none is given.
+const DEFAULT_BASELINE: &str = "spellcheck-baseline.json";
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum ExitCode {
     Success,
@@ -82,15 +102,27 @@ struct Args {
     flag_version: bool,
     flag_help: bool,
     flag_checkers: Option<String>,
+    flag_jobs: Option<usize>,
+    flag_no_cache: bool,
+    flag_changed_since: Option<String>,
     flag_cfg: Option<PathBuf>,
     flag_force: bool,
     flag_user: bool,
     flag_skip_readme: bool,
     flag_code: u8,
     flag_stdout: bool,
+    flag_format: OutputFormat,
+    flag_auto: bool,
+    flag_dry_run: bool,
+    flag_allow: Option<String>,
+    flag_patch: Option<PathBuf>,
+    flag_baseline: Option<PathBuf>,
     cmd_fix: bool,
+    cmd_baseline: bool,
     cmd_check: bool,
     cmd_config: bool,
+    cmd_reflow: bool,
+    cmd_lsp: bool,
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -171,6 +203,13 @@ fn run() -> anyhow::Result<ExitCode> {
         return Ok(ExitCode::Success);
     }
 
+    // the interactive fixer would write the files regardless
+    if !args.flag_auto && (args.flag_dry_run || args.flag_allow.is_some()) {
+        return Err(anyhow::anyhow!(
+            "`--dry-run` and `--allow` require `--auto`."
+        ));
+    }
+
     #[cfg(not(target_os = "windows"))]
     std::thread::spawn(move || signal_handler());
 
@@ -181,6 +220,12 @@ fn run() -> anyhow::Result<ExitCode> {
                 .split(',')
                 .map(|checker| checker.to_lowercase())
                 .collect::<Vec<_>>();
+            if !checkers.contains(&"native".to_owned()) {
+                config.native.take();
+            } else if config.native.is_none() {
+                // the same dictionaries, without `libhunspell`
+                config.native = config.hunspell.clone();
+            }
             if !checkers.contains(&"hunspell".to_owned()) {
                 if !config.hunspell.take().is_some() {
                     warn!("Hunspell was never configured.")
@@ -191,6 +236,21 @@ fn run() -> anyhow::Result<ExitCode> {
                     warn!("Languagetool was never configured.")
                 }
             }
+            if !checkers.contains(&"symbols".to_owned()) {
+                if config.symbols.take().is_none() {
+                    warn!("Symbols was never configured.")
+                }
+            }
+            if !checkers.contains(&"links".to_owned()) {
+                if config.links.take().is_none() {
+                    warn!("Links was never configured.")
+                }
+            }
+            if !checkers.contains(&"command".to_owned()) {
+                if config.command.take().is_none() {
+                    warn!("Command was never configured.")
+                }
+            }
         }
     };
 
@@ -311,26 +371,124 @@ fn run() -> anyhow::Result<ExitCode> {
 
     checkers(&mut config);
 
+    let jobs = args.flag_jobs.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    });
+
+    // handle `lsp` sub command, which serves until the editor exits
+    if args.cmd_lsp {
+        let stdin = std::io::stdin();
+        let stdout = std::io::stdout();
+        lsp::Server::new(config, jobs).serve(stdin.lock(), stdout.lock())?;
+        return Ok(ExitCode::Success);
+    }
+
     // extract operation mode
-    let action = if args.cmd_fix || args.flag_fix {
-        Action::Fix
+    let destination = match args.flag_patch {
+        Some(ref path) if path.as_os_str() == "-" => Destination::Patch(None),
+        Some(ref path) => Destination::Patch(Some(path.to_owned())),
+        None => Destination::InPlace,
+    };
+    let action = if args.cmd_fix && args.flag_auto {
+        let detectors = if let Some(ref allow) = args.flag_allow {
+            allow
+                .split(',')
+                .map(|detector| detector.parse::<Detector>())
+                .collect::<anyhow::Result<BitFlags<Detector>>>()?
+        } else {
+            BitFlags::all()
+        };
+        Action::AutoFix {
+            dry_run: args.flag_dry_run,
+            detectors,
+            destination,
+        }
+    } else if args.cmd_fix || args.flag_fix {
+        Action::Fix(destination)
+    } else if args.cmd_reflow {
+        Action::Reflow(destination)
+    } else if args.cmd_baseline {
+        Action::Baseline(
+            args.flag_baseline
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_BASELINE)),
+        )
     } else {
         // check
-        Action::Check
+        Action::Check(args.flag_format)
     };
 
     trace!("Executing: {:?} with {:?}", action, &config);
 
-    let combined = traverse::extract(
-        args.arg_paths,
-        args.flag_recursive,
-        args.flag_skip_readme,
-        &config,
-    )?;
+    // narrow down to the files touched since the given revision
+    let (paths, recursive, changed) = if let Some(ref rev) = args.flag_changed_since {
+        let cwd = traverse::cwd()?;
+        let changed = traverse::ChangedLines::since(rev, &cwd)?;
+        let files = changed.files(&args.arg_paths, &cwd);
+        if files.is_empty() {
+            info!("No files with documentation changed since {}", rev);
+            return Ok(ExitCode::Success);
+        }
+        (files, false, Some(changed))
+    } else {
+        (args.arg_paths, args.flag_recursive, None)
+    };
 
-    let suggestion_set = checker::check(&combined, &config)?;
+    let combined = traverse::extract(paths, recursive, args.flag_skip_readme, &config)?;
+
+    let cache_dir = if args.flag_no_cache {
+        None
+    } else {
+        Some(checker::cache::default_dir(&traverse::cwd()?))
+    };
+    // only report findings which were not accepted before
+    let mut baseline = match (&action, args.flag_baseline) {
+        (Action::Check(_), Some(path)) => {
+            let baseline = Baseline::load(&path)?;
+            debug!(
+                "Ignoring up to {} findings recorded in baseline {}",
+                baseline.total_count(),
+                path.display()
+            );
+            Some(baseline)
+        }
+        _ => None,
+    };
+    let root = traverse::project_root(&traverse::cwd()?);
 
-    let finish = action.run(suggestion_set, &config)?;
+    let finish = match action {
+        Action::Reflow(_) => {
+            // re-wrapping does not depend on any findings
+            action.run(&combined, SuggestionSet::new(), &config)?
+        }
+        Action::Check(OutputFormat::NdJson) => {
+            // print the findings of every file as soon as it is checked
+            let stdout = std::io::stdout();
+            let mut count = 0;
+            checker::check_with_sink(
+                &combined,
+                &config,
+                jobs,
+                cache_dir.as_deref(),
+                &mut |origin, suggestions| {
+                    let mut suggestion_set = SuggestionSet::new();
+                    suggestion_set.extend(origin.clone(), suggestions.iter().cloned());
+                    let suggestion_set =
+                        narrow(suggestion_set, changed.as_ref(), baseline.as_mut(), &root);
+                    count += suggestion_set.total_count();
+                    OutputFormat::NdJson.emit(&suggestion_set, stdout.lock())
+                },
+            )?;
+            Finish::MistakeCount(count)
+        }
+        action => {
+            let suggestion_set = checker::check(&combined, &config, jobs, cache_dir.as_deref())?;
+            let suggestion_set = narrow(suggestion_set, changed.as_ref(), baseline.as_mut(), &root);
+            action.run(&combined, suggestion_set, &config)?
+        }
+    };
 
     match finish {
         Finish::MistakeCount(0) => Ok(ExitCode::Success),
@@ -339,6 +497,23 @@ fn run() -> anyhow::Result<ExitCode> {
     }
 }
 
+/// Retain only the findings on `changed` lines, which are not covered by the `baseline`.
+fn narrow<'s>(
+    suggestion_set: SuggestionSet<'s>,
+    changed: Option<&traverse::ChangedLines>,
+    baseline: Option<&mut Baseline>,
+    root: &Path,
+) -> SuggestionSet<'s> {
+    let suggestion_set = match changed {
+        Some(changed) => changed.filter(suggestion_set),
+        None => suggestion_set,
+    };
+    match baseline {
+        Some(baseline) => baseline.consume(suggestion_set, root),
+        None => suggestion_set,
+    }
+}
+
 fn main() -> anyhow::Result<()> {
     std::process::exit(run()?.as_u8() as i32)
 }
@@ -366,6 +541,34 @@ mod tests {
             "cargo-spellcheck -q fix Cargo.toml",
             "cargo spellcheck -v fix Cargo.toml",
             "cargo spellcheck -m 11 check",
+            "cargo spellcheck check --format=json",
+            "cargo-spellcheck check --format=ndjson -r src",
+            "cargo-spellcheck check --format=sarif",
+            "cargo-spellcheck check --format=short",
+            "cargo spellcheck check --format=github",
+            "cargo spellcheck check --format=gitlab",
+            "cargo-spellcheck fix --auto",
+            "cargo-spellcheck fix --auto --dry-run --allow=hunspell",
+            "cargo-spellcheck fix --auto --allow=hunspell,languagetool -r src",
+            "cargo-spellcheck fix --patch=-",
+            "cargo-spellcheck fix --auto --patch=spelling.patch",
+            "cargo-spellcheck --fix --patch=spelling.patch",
+            "cargo-spellcheck check -j 4",
+            "cargo-spellcheck fix --jobs=2 -r src",
+            "cargo-spellcheck --jobs=1",
+            "cargo-spellcheck check --no-cache",
+            "cargo spellcheck --no-cache --fix",
+            "cargo-spellcheck check --changed-since=HEAD",
+            "cargo-spellcheck fix --changed-since=origin/master src",
+            "cargo-spellcheck --changed-since=HEAD~1 --format=short",
+            "cargo-spellcheck baseline",
+            "cargo-spellcheck reflow",
+            "cargo-spellcheck reflow --patch=- -r src",
+            "cargo-spellcheck lsp",
+            "cargo spellcheck -vvv lsp --cfg=spellcheck.toml --checkers=symbols",
+            "cargo-spellcheck baseline --baseline=spelling.json -r src",
+            "cargo-spellcheck check --baseline=spelling.json",
+            "cargo-spellcheck check --format=github --baseline=spelling.json",
         ];
         for command in commands {
             assert!(parse_args(commandline_to_iter(command)).is_ok());
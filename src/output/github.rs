@@ -0,0 +1,84 @@
+//! GitHub Actions workflow commands, rendered as inline annotations.
+//!
+//! ```text
+//! ::warning file=src/main.rs,line=3,col=5,endLine=3,endColumn=9,title=spellcheck(Hunspell)::Possible spelling mistake found. `dyrck` - dyck, dark
+//! ```
+//!
+//! See <https://docs.github.com/en/actions/reference/workflow-commands-for-github-actions>.
+
+use super::display_path;
+use crate::util::sub_chars;
+use crate::Suggestion;
+
+use anyhow::Result;
+use std::io::Write;
+
+/// Escape the message part of a workflow command.
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property value of a workflow command.
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Write a single suggestion as `::warning` command.
+pub(super) fn emit_github_line<W: Write>(suggestion: &Suggestion, mut sink: W) -> Result<()> {
+    let word = sub_chars(suggestion.chunk.as_str(), suggestion.range.clone());
+    let mut message = if let Some(ref description) = suggestion.description {
+        format!("{} `{}`", description, word)
+    } else {
+        format!("Possible mistake `{}`", word)
+    };
+    if !suggestion.replacements.is_empty() {
+        message.push_str(" - ");
+        message.push_str(suggestion.replacements.join(", ").as_str());
+    }
+
+    let span = suggestion.span;
+    writeln!(
+        sink,
+        "::warning file={file},line={line},col={col},endLine={end_line},endColumn={end_col},title={title}::{message}",
+        file = escape_property(display_path(suggestion.origin.as_path()).as_str()),
+        line = span.start.line,
+        col = span.start.column + 1,
+        end_line = span.end.line,
+        end_col = span.end.column + 1,
+        title = escape_property(format!("spellcheck({})", suggestion.detector).as_str()),
+        message = escape_data(message.as_str()),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{chunk, suggestion};
+    use super::*;
+    use crate::documentation::ContentOrigin;
+    use crate::{OutputFormat, SuggestionSet};
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape_data("50%\nmore"), "50%25%0Amore");
+        assert_eq!(escape_property("a:b,c"), "a%3Ab%2Cc");
+    }
+
+    #[test]
+    fn github() {
+        let chunk = chunk();
+        let mut set = SuggestionSet::new();
+        set.add(ContentOrigin::TestEntity, suggestion(&chunk));
+
+        let mut sink = Vec::<u8>::with_capacity(256);
+        OutputFormat::GitHub
+            .emit(&set, &mut sink)
+            .expect("Writing to a vec never fails");
+        assert_eq!(
+            String::from_utf8(sink).expect("Must be valid utf8"),
+            "::warning file=/tmp/test/entity,line=1,col=7,endLine=1,endColumn=11,title=spellcheck(Dummy)::Possible spelling mistake found. `dyrck` - dyck, dark\n"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! GitLab Code Quality report.
+//!
+//! A JSON array of issues, a subset of the Code Climate format,
+//! see <https://docs.gitlab.com/ee/user/project/merge_requests/code_quality.html>.
+
+use super::display_path;
+use crate::action::baseline::context;
+use crate::util::{fnv1a64, sub_chars};
+use crate::{Suggestion, SuggestionSet};
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeQualityIssue {
+    pub description: String,
+    pub check_name: String,
+    /// Unique and stable across runs, used to track an issue between merge requests.
+    ///
+    /// Derived from the path, the detector, the flagged word and the trimmed line it
+    /// was found in, as for the baseline, so edits above an issue do not change it.
+    pub fingerprint: String,
+    pub severity: &'static str,
+    pub location: CodeQualityLocation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeQualityLocation {
+    pub path: String,
+    pub lines: CodeQualityLines,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CodeQualityLines {
+    pub begin: usize,
+}
+
+impl<'a, 's> From<&'a Suggestion<'s>> for CodeQualityIssue {
+    fn from(suggestion: &'a Suggestion<'s>) -> Self {
+        let path = display_path(suggestion.origin.as_path());
+        let word = sub_chars(suggestion.chunk.as_str(), suggestion.range.clone());
+        let mut description = if let Some(ref description) = suggestion.description {
+            format!("{} `{}`", description, word)
+        } else {
+            format!("Possible mistake `{}`", word)
+        };
+        if !suggestion.replacements.is_empty() {
+            description.push_str(" - ");
+            description.push_str(suggestion.replacements.join(", ").as_str());
+        }
+
+        let span = suggestion.span;
+        let fingerprint = format!(
+            "{}:{}:{}:{}",
+            path,
+            suggestion.detector,
+            word,
+            context(suggestion.chunk.as_str(), suggestion.range.clone())
+        );
+        let fingerprint = format!("{:016x}", fnv1a64(fingerprint.as_bytes()));
+
+        Self {
+            description,
+            check_name: suggestion.detector.to_string(),
+            fingerprint,
+            severity: "minor",
+            location: CodeQualityLocation {
+                path,
+                lines: CodeQualityLines {
+                    begin: span.start.line,
+                },
+            },
+        }
+    }
+}
+
+/// Collect all issues of a suggestion set.
+pub fn code_quality_report(set: &SuggestionSet) -> Vec<CodeQualityIssue> {
+    let mut occurrences = HashMap::<String, usize>::new();
+    set.iter()
+        .flat_map(|(_origin, suggestions)| suggestions.iter())
+        .map(|suggestion| {
+            let mut issue = CodeQualityIssue::from(suggestion);
+            // the same mistake twice within one line still needs distinct fingerprints
+            let occurrence = occurrences.entry(issue.fingerprint.clone()).or_insert(0);
+            if *occurrence > 0 {
+                let fingerprint = format!("{}:{}", issue.fingerprint, occurrence);
+                issue.fingerprint = format!("{:016x}", fnv1a64(fingerprint.as_bytes()));
+            }
+            *occurrence += 1;
+            issue
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{chunk, suggestion};
+    use super::CodeQualityIssue;
+    use crate::documentation::ContentOrigin;
+    use crate::{OutputFormat, SuggestionSet};
+
+    #[test]
+    fn gitlab() {
+        let chunk = chunk();
+        let mut set = SuggestionSet::new();
+        set.add(ContentOrigin::TestEntity, suggestion(&chunk));
+        let mut other = suggestion(&chunk);
+        other.span.start.line = 2;
+        other.span.end.line = 2;
+        set.add(ContentOrigin::TestEntity, other);
+
+        let mut sink = Vec::<u8>::with_capacity(1024);
+        OutputFormat::GitLab
+            .emit(&set, &mut sink)
+            .expect("Writing to a vec never fails");
+        let value: serde_json::Value =
+            serde_json::from_slice(sink.as_slice()).expect("Must be valid json");
+        let issues = value.as_array().expect("Must be an array");
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0]["check_name"], "Dummy");
+        assert_eq!(issues[0]["location"]["path"], "/tmp/test/entity");
+        assert_eq!(issues[0]["location"]["lines"]["begin"], 1);
+        assert_eq!(issues[1]["location"]["lines"]["begin"], 2);
+        assert_ne!(issues[0]["fingerprint"], issues[1]["fingerprint"]);
+
+        // moving the line does not change the fingerprint
+        let mut moved = suggestion(&chunk);
+        moved.span.start.line = 7;
+        moved.span.end.line = 7;
+        assert_eq!(
+            CodeQualityIssue::from(&moved).fingerprint,
+            issues[0]["fingerprint"]
+        );
+    }
+}
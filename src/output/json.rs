@@ -0,0 +1,220 @@
+//! Machine readable JSON representation of suggestions.
+//!
+//! The schema is versioned by `SCHEMA_VERSION`, which must be increased
+//! on any change that is not purely additive.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "suggestions": [
+//!     {
+//!       "detector": "Hunspell",
+//!       "origin": "RustSourceFile",
+//!       "path": "src/main.rs",
+//!       "span": {
+//!         "start": { "line": 3, "column": 4 },
+//!         "end": { "line": 3, "column": 8 }
+//!       },
+//!       "range": { "start": 1, "end": 6 },
+//!       "replacements": ["Thing"],
+//!       "description": "Possible spelling mistake found."
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Line numbers are 1-indexed, columns 0-indexed and the span end is inclusive.
+//! The `range` is relative to the checked chunk and its end is exclusive.
+
+use crate::documentation::ContentOrigin;
+use crate::{Detector, LineColumn, Range, Span, Suggestion, SuggestionSet};
+
+use serde::Serialize;
+use std::path::Path;
+
+/// Version of the JSON schema emitted.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct JsonLineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl From<LineColumn> for JsonLineColumn {
+    fn from(lc: LineColumn) -> Self {
+        Self {
+            line: lc.line,
+            column: lc.column,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct JsonSpan {
+    pub start: JsonLineColumn,
+    pub end: JsonLineColumn,
+}
+
+impl From<Span> for JsonSpan {
+    fn from(span: Span) -> Self {
+        Self {
+            start: span.start.into(),
+            end: span.end.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct JsonRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl From<Range> for JsonRange {
+    fn from(range: Range) -> Self {
+        Self {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+/// Serializable view of a single `Suggestion`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonSuggestion<'a> {
+    pub detector: Detector,
+    /// Kind of the origin, i.e. `RustSourceFile` or `CommonMarkFile`.
+    pub origin: &'static str,
+    pub path: &'a Path,
+    pub span: JsonSpan,
+    pub range: JsonRange,
+    pub replacements: &'a [String],
+    pub description: Option<&'a str>,
+}
+
+impl<'a, 's> From<&'a Suggestion<'s>> for JsonSuggestion<'a> {
+    fn from(suggestion: &'a Suggestion<'s>) -> Self {
+        let origin = match suggestion.origin {
+            ContentOrigin::CargoManifest(_) => "CargoManifest",
+            ContentOrigin::CommonMarkFile(_) => "CommonMarkFile",
+            ContentOrigin::RustDocTest(_, _) => "RustDocTest",
+            ContentOrigin::RustSourceFile(_) => "RustSourceFile",
+            #[cfg(test)]
+            ContentOrigin::TestEntity => "TestEntity",
+        };
+        Self {
+            detector: suggestion.detector,
+            origin,
+            path: suggestion.origin.as_path(),
+            span: suggestion.span.into(),
+            range: suggestion.range.clone().into(),
+            replacements: suggestion.replacements.as_slice(),
+            description: suggestion.description.as_deref(),
+        }
+    }
+}
+
+/// The complete document as emitted by `--format=json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonDocument<'a> {
+    pub version: u32,
+    pub suggestions: Vec<JsonSuggestion<'a>>,
+}
+
+impl<'a, 's> From<&'a SuggestionSet<'s>> for JsonDocument<'a> {
+    fn from(set: &'a SuggestionSet<'s>) -> Self {
+        let suggestions = set
+            .iter()
+            .flat_map(|(_origin, suggestions)| suggestions.iter())
+            .map(JsonSuggestion::from)
+            .collect();
+        Self {
+            version: SCHEMA_VERSION,
+            suggestions,
+        }
+    }
+}
+
+/// A single line as emitted by `--format=ndjson`, self contained including the schema version.
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonLine<'a> {
+    pub version: u32,
+    #[serde(flatten)]
+    pub suggestion: JsonSuggestion<'a>,
+}
+
+impl<'a, 's> From<&'a Suggestion<'s>> for JsonLine<'a> {
+    fn from(suggestion: &'a Suggestion<'s>) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            suggestion: JsonSuggestion::from(suggestion),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{chunk, suggestion};
+    use super::*;
+    use crate::OutputFormat;
+
+    #[test]
+    fn json_document() {
+        let chunk = chunk();
+        let mut set = SuggestionSet::new();
+        set.add(ContentOrigin::TestEntity, suggestion(&chunk));
+
+        let mut sink = Vec::<u8>::with_capacity(1024);
+        OutputFormat::Json
+            .emit(&set, &mut sink)
+            .expect("Writing to a vec never fails");
+
+        let value: serde_json::Value =
+            serde_json::from_slice(sink.as_slice()).expect("Must be valid json");
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "version": SCHEMA_VERSION,
+                "suggestions": [
+                    {
+                        "detector": "Dummy",
+                        "origin": "TestEntity",
+                        "path": "/tmp/test/entity",
+                        "span": {
+                            "start": { "line": 1, "column": 6 },
+                            "end": { "line": 1, "column": 10 }
+                        },
+                        "range": { "start": 7, "end": 12 },
+                        "replacements": ["dyck", "dark"],
+                        "description": "Possible spelling mistake found."
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn ndjson_lines() {
+        let chunk = chunk();
+        let mut set = SuggestionSet::new();
+        set.add(ContentOrigin::TestEntity, suggestion(&chunk));
+        set.add(ContentOrigin::TestEntity, suggestion(&chunk));
+
+        let mut sink = Vec::<u8>::with_capacity(1024);
+        OutputFormat::NdJson
+            .emit(&set, &mut sink)
+            .expect("Writing to a vec never fails");
+
+        let output = String::from_utf8(sink).expect("Must be valid utf8");
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        for line in lines {
+            let value: serde_json::Value =
+                serde_json::from_str(line).expect("Every line must be valid json");
+            assert_eq!(value["version"], SCHEMA_VERSION);
+            assert_eq!(value["detector"], "Dummy");
+            assert_eq!(value["span"]["start"]["column"], 6);
+        }
+    }
+}
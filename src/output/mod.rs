@@ -0,0 +1,173 @@
+//! Output formats for the findings of a `check` run.
+//!
+//! The default is the human readable, coloured `Display` impl of `Suggestion`,
+//! the others are meant to be consumed by machines, i.e. CI scripts, or
+//! to annotate merge requests.
+
+use crate::{Suggestion, SuggestionSet};
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::io::Write;
+use std::path::Path;
+
+mod github;
+mod gitlab;
+mod json;
+mod sarif;
+mod short;
+
+pub use gitlab::*;
+pub use json::*;
+pub use sarif::*;
+
+use github::emit_github_line;
+use short::emit_short_line;
+
+/// Format in which the found suggestions are printed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Default)]
+pub enum OutputFormat {
+    /// Colourful, boxed output, meant for humans.
+    #[default]
+    Human,
+    /// One JSON document containing all suggestions.
+    Json,
+    /// One JSON object per line and suggestion, written as soon as a file is checked.
+    NdJson,
+    /// A SARIF 2.1.0 log, as ingested by code scanning dashboards.
+    Sarif,
+    /// One line per suggestion `path:line:column: detector: word -> replacements`.
+    Short,
+    /// GitHub Actions `::warning` workflow commands.
+    GitHub,
+    /// GitLab Code Quality JSON report.
+    GitLab,
+}
+
+impl OutputFormat {
+    /// Write all suggestions to `sink` in the format of `self`.
+    pub fn emit<W: Write>(&self, suggestions: &SuggestionSet, mut sink: W) -> Result<()> {
+        match self {
+            Self::Human => {
+                for (_origin, suggestions) in suggestions.iter() {
+                    for suggestion in suggestions {
+                        writeln!(sink, "{}", suggestion)?;
+                    }
+                }
+            }
+            Self::Json => {
+                let document = JsonDocument::from(suggestions);
+                serde_json::to_writer_pretty(&mut sink, &document)?;
+                writeln!(sink)?;
+            }
+            Self::NdJson => {
+                for (_origin, suggestions) in suggestions.iter() {
+                    for suggestion in suggestions {
+                        emit_ndjson_line(suggestion, &mut sink)?;
+                    }
+                }
+            }
+            Self::Sarif => {
+                let log = SarifLog::from(suggestions);
+                serde_json::to_writer_pretty(&mut sink, &log)?;
+                writeln!(sink)?;
+            }
+            Self::Short => {
+                for (_origin, suggestions) in suggestions.iter() {
+                    for suggestion in suggestions {
+                        emit_short_line(suggestion, &mut sink)?;
+                    }
+                }
+            }
+            Self::GitHub => {
+                for (_origin, suggestions) in suggestions.iter() {
+                    for suggestion in suggestions {
+                        emit_github_line(suggestion, &mut sink)?;
+                    }
+                }
+            }
+            Self::GitLab => {
+                let report = code_quality_report(suggestions);
+                serde_json::to_writer_pretty(&mut sink, &report)?;
+                writeln!(sink)?;
+            }
+        }
+        sink.flush()?;
+        Ok(())
+    }
+}
+
+/// Write a single suggestion as one line of JSON.
+fn emit_ndjson_line<W: Write>(suggestion: &Suggestion, mut sink: W) -> Result<()> {
+    let line = JsonLine::from(suggestion);
+    serde_json::to_writer(&mut sink, &line)?;
+    writeln!(sink)?;
+    Ok(())
+}
+
+/// Express `path` relative to the current working directory, if it is located within.
+pub(crate) fn relative_to_cwd(path: &Path) -> Option<String> {
+    relative_to(&std::env::current_dir().ok()?, path)
+}
+
+/// `path` relative to `base` with `/` as separator, if it is within `base`.
+pub(crate) fn relative_to(base: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(base).ok()?;
+    Some(
+        relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
+
+/// A path as displayed to the user, relative to the current working directory if possible.
+pub(crate) fn display_path(path: &Path) -> String {
+    relative_to_cwd(path).unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::documentation::{CheckableChunk, ContentOrigin};
+    use crate::{Detector, LineColumn, Span, Suggestion};
+
+    const CONTENT: &str = " Is it dyrck again?";
+
+    /// A single line chunk, the suggestions refer to.
+    pub(crate) fn chunk() -> CheckableChunk {
+        CheckableChunk::from_str(
+            CONTENT,
+            indexmap::indexmap! { 0..18 => Span {
+                    start: LineColumn {
+                        line: 1,
+                        column: 0,
+                    },
+                    end: LineColumn {
+                        line: 1,
+                        column: 17,
+                    }
+                }
+            },
+        )
+    }
+
+    /// A suggestion for `dyrck` with two replacements.
+    pub(crate) fn suggestion(chunk: &CheckableChunk) -> Suggestion<'_> {
+        Suggestion {
+            detector: Detector::Dummy,
+            origin: ContentOrigin::TestEntity,
+            chunk,
+            range: 7..12,
+            span: Span {
+                start: LineColumn { line: 1, column: 6 },
+                end: LineColumn {
+                    line: 1,
+                    column: 10,
+                },
+            },
+            replacements: vec!["dyck".to_owned(), "dark".to_owned()],
+            description: Some("Possible spelling mistake found.".to_owned()),
+        }
+    }
+}
@@ -0,0 +1,308 @@
+//! Static Analysis Results Interchange Format (SARIF) 2.1.0 report.
+//!
+//! Each `Detector` becomes a rule of the `cargo-spellcheck` tool, each
+//! suggestion a result with a physical location and one fix per replacement.
+//!
+//! Note that SARIF regions are 1-indexed for both lines and columns and
+//! the `endColumn` is exclusive, unlike `Span`.
+
+use super::relative_to_cwd;
+use crate::util::sub_chars;
+use crate::{Detector, Span, Suggestion, SuggestionSet};
+
+use serde::Serialize;
+use std::path::Path;
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub runs: Vec<Run>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Run {
+    pub tool: Tool,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Tool {
+    pub driver: Driver,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Driver {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub information_uri: &'static str,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub id: String,
+    pub name: String,
+    pub short_description: Message,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Message {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub rule_index: usize,
+    pub level: &'static str,
+    pub message: Message,
+    pub locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fixes: Vec<Fix>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+    pub artifact_location: ArtifactLocation,
+    pub region: Region,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtifactLocation {
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl From<Span> for Region {
+    fn from(span: Span) -> Self {
+        Self {
+            start_line: span.start.line,
+            start_column: span.start.column + 1,
+            end_line: span.end.line,
+            // inclusive 0-indexed to exclusive 1-indexed
+            end_column: span.end.column + 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fix {
+    pub description: Message,
+    pub artifact_changes: Vec<ArtifactChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactChange {
+    pub artifact_location: ArtifactLocation,
+    pub replacements: Vec<Replacement>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Replacement {
+    pub deleted_region: Region,
+    pub inserted_content: InsertedContent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InsertedContent {
+    pub text: String,
+}
+
+/// Convert a path into a uri, relative to the current working dir if possible.
+fn artifact_uri(path: &Path) -> String {
+    if let Some(relative) = relative_to_cwd(path) {
+        return relative;
+    }
+    url::Url::from_file_path(path)
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| path.display().to_string())
+}
+
+fn rule_description(detector: Detector) -> &'static str {
+    match detector {
+        Detector::Hunspell => "Spelling mistakes found by a dictionary lookup with hunspell.",
+        Detector::LanguageTool => "Spelling and grammar mistakes found by LanguageTool.",
+        Detector::Symbols => "Inline code resembling, but not matching, a symbol of the crate.",
+        Detector::Links => "Links to items, files or anchors which do not exist.",
+        Detector::Command => "Findings of the configured external command.",
+        Detector::Native => {
+            "Spelling mistakes found by a dictionary lookup with hunspell dictionaries."
+        }
+        #[cfg(test)]
+        Detector::Dummy => "Everything is wrong.",
+    }
+}
+
+impl<'a, 's> From<&'a SuggestionSet<'s>> for SarifLog {
+    fn from(set: &'a SuggestionSet<'s>) -> Self {
+        let mut detectors = Vec::<Detector>::with_capacity(4);
+        let mut results = Vec::with_capacity(set.total_count());
+
+        for suggestion in set
+            .iter()
+            .flat_map(|(_origin, suggestions)| suggestions.iter())
+        {
+            let rule_index = if let Some(idx) = detectors
+                .iter()
+                .position(|detector| *detector == suggestion.detector)
+            {
+                idx
+            } else {
+                detectors.push(suggestion.detector);
+                detectors.len() - 1
+            };
+            results.push(SarifResult::from_suggestion(suggestion, rule_index));
+        }
+
+        let rules = detectors
+            .into_iter()
+            .map(|detector| Rule {
+                id: detector.to_string(),
+                name: detector.to_string(),
+                short_description: Message {
+                    text: rule_description(detector).to_owned(),
+                },
+            })
+            .collect();
+
+        Self {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: env!("CARGO_PKG_NAME"),
+                        version: env!("CARGO_PKG_VERSION"),
+                        information_uri: env!("CARGO_PKG_HOMEPAGE"),
+                        rules,
+                    },
+                },
+                results,
+            }],
+        }
+    }
+}
+
+impl SarifResult {
+    fn from_suggestion(suggestion: &Suggestion, rule_index: usize) -> Self {
+        let uri = artifact_uri(suggestion.origin.as_path());
+        let region = Region::from(suggestion.span);
+        let word = sub_chars(suggestion.chunk.as_str(), suggestion.range.clone());
+
+        let text = if let Some(ref description) = suggestion.description {
+            format!("{} `{}`", description, word)
+        } else {
+            format!("Possible mistake `{}`", word)
+        };
+
+        let fixes = suggestion
+            .replacements
+            .iter()
+            .map(|replacement| Fix {
+                description: Message {
+                    text: format!("Replace `{}` with `{}`", word, replacement),
+                },
+                artifact_changes: vec![ArtifactChange {
+                    artifact_location: ArtifactLocation { uri: uri.clone() },
+                    replacements: vec![Replacement {
+                        deleted_region: region,
+                        inserted_content: InsertedContent {
+                            text: replacement.to_owned(),
+                        },
+                    }],
+                }],
+            })
+            .collect();
+
+        Self {
+            rule_id: suggestion.detector.to_string(),
+            rule_index,
+            level: "warning",
+            message: Message { text },
+            locations: vec![Location {
+                physical_location: PhysicalLocation {
+                    artifact_location: ArtifactLocation { uri },
+                    region,
+                },
+            }],
+            fixes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documentation::ContentOrigin;
+    use crate::output::tests::{chunk, suggestion};
+    use crate::OutputFormat;
+
+    #[test]
+    fn sarif_report() {
+        let chunk = chunk();
+        let mut set = SuggestionSet::new();
+        set.add(ContentOrigin::TestEntity, suggestion(&chunk));
+
+        let mut sink = Vec::<u8>::with_capacity(2048);
+        OutputFormat::Sarif
+            .emit(&set, &mut sink)
+            .expect("Writing to a vec never fails");
+        let value: serde_json::Value =
+            serde_json::from_slice(sink.as_slice()).expect("Must be valid json");
+
+        assert_eq!(value["version"], SARIF_VERSION);
+        let run = &value["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "cargo-spellcheck");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "Dummy");
+
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "Dummy");
+        assert_eq!(result["ruleIndex"], 0);
+        assert_eq!(
+            result["message"]["text"],
+            "Possible spelling mistake found. `dyrck`"
+        );
+        let region = &result["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(
+            region,
+            &serde_json::json!({
+                "startLine": 1,
+                "startColumn": 7,
+                "endLine": 1,
+                "endColumn": 12,
+            })
+        );
+
+        let fixes = result["fixes"].as_array().expect("Must have fixes");
+        assert_eq!(fixes.len(), 2);
+        let replacement = &fixes[1]["artifactChanges"][0]["replacements"][0];
+        assert_eq!(replacement["insertedContent"]["text"], "dark");
+        assert_eq!(&replacement["deletedRegion"], region);
+    }
+}
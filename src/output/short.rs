@@ -0,0 +1,59 @@
+//! One line per suggestion, similar to `cargo --message-format=short`.
+//!
+//! ```text
+//! src/main.rs:3:5: Hunspell: dyrck -> dyck, dark
+//! ```
+//!
+//! Lines and columns are both 1-indexed.
+
+use super::display_path;
+use crate::util::sub_chars;
+use crate::Suggestion;
+
+use anyhow::Result;
+use std::io::Write;
+
+/// Write a single suggestion as one line.
+pub(super) fn emit_short_line<W: Write>(suggestion: &Suggestion, mut sink: W) -> Result<()> {
+    let word = sub_chars(suggestion.chunk.as_str(), suggestion.range.clone());
+    write!(
+        sink,
+        "{path}:{line}:{column}: {detector}: {word}",
+        path = display_path(suggestion.origin.as_path()),
+        line = suggestion.span.start.line,
+        column = suggestion.span.start.column + 1,
+        detector = suggestion.detector,
+        word = word,
+    )?;
+    if !suggestion.replacements.is_empty() {
+        write!(sink, " -> {}", suggestion.replacements.join(", "))?;
+    }
+    writeln!(sink)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{chunk, suggestion};
+    use crate::documentation::ContentOrigin;
+    use crate::{OutputFormat, SuggestionSet};
+
+    #[test]
+    fn short() {
+        let chunk = chunk();
+        let mut set = SuggestionSet::new();
+        set.add(ContentOrigin::TestEntity, suggestion(&chunk));
+        let mut without = suggestion(&chunk);
+        without.replacements.clear();
+        set.add(ContentOrigin::TestEntity, without);
+
+        let mut sink = Vec::<u8>::with_capacity(256);
+        OutputFormat::Short
+            .emit(&set, &mut sink)
+            .expect("Writing to a vec never fails");
+        assert_eq!(
+            String::from_utf8(sink).expect("Must be valid utf8"),
+            "/tmp/test/entity:1:7: Dummy: dyrck -> dyck, dark\n/tmp/test/entity:1:7: Dummy: dyrck\n"
+        );
+    }
+}
@@ -62,7 +62,7 @@ impl Span {
 
     /// Check if `self` span covers provided `line` number, which is 1-indexed.
     pub fn covers_line(&self, line: usize) -> bool {
-        self.end.line <= line && line >= self.start.line
+        self.start.line <= line && line <= self.end.line
     }
 
     /// If this one resembles a single line, returns the a `Some(len)` value.
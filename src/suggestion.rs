@@ -17,15 +17,20 @@ use std::cmp;
 use std::convert::TryFrom;
 
 use enumflags2::BitFlags;
+use serde::Serialize;
 
 use crate::{Range, Span};
 
 /// Bitflag of available checkers by compilation / configuration.
-#[derive(Debug, Clone, Copy, BitFlags, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, BitFlags, Eq, PartialEq, Hash, Serialize)]
 #[repr(u8)]
 pub enum Detector {
     Hunspell = 0b0001,
     LanguageTool = 0b0010,
+    Symbols = 0b0100,
+    Links = 0b1_0000,
+    Command = 0b10_0000,
+    Native = 0b100_0000,
     #[cfg(test)]
     Dummy = 0b1000,
 }
@@ -63,12 +68,35 @@ impl fmt::Display for Detector {
         formatter.write_str(match self {
             Self::LanguageTool => "LanguageTool",
             Self::Hunspell => "Hunspell",
+            Self::Symbols => "Symbols",
+            Self::Links => "Links",
+            Self::Command => "Command",
+            Self::Native => "Native",
             #[cfg(test)]
             Self::Dummy => "Dummy",
         })
     }
 }
 
+impl std::str::FromStr for Detector {
+    type Err = anyhow::Error;
+
+    /// Parse the case insensitive name of a detector.
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "hunspell" => Self::Hunspell,
+            "languagetool" => Self::LanguageTool,
+            "symbols" => Self::Symbols,
+            "links" => Self::Links,
+            "command" => Self::Command,
+            "native" => Self::Native,
+            #[cfg(test)]
+            "dummy" => Self::Dummy,
+            other => anyhow::bail!("Unknown detector {}", other),
+        })
+    }
+}
+
 // For long lines, literal will be trimmed to display in one terminal line.
 // Misspelled words that are too long shall also be ellipsized.
 pub fn condition_display_content(
@@ -314,13 +342,9 @@ impl<'s> fmt::Display for Suggestion<'s> {
             .apply_to(format!("{:>width$}", "-->", width = indent + 1))
             .fmt(formatter)?;
 
-        let x = self.span.start.line;
-        let (path, line) = match self.origin {
-            ContentOrigin::RustDocTest(ref path, ref span) => {
-                (path.display().to_string(), x + span.start.line)
-            }
-            ref origin => (origin.as_path().display().to_string(), x),
-        };
+        // spans of doc tests are already relative to the file containing them
+        let path = self.origin.as_path().display().to_string();
+        let line = self.span.start.line;
         writeln!(formatter, " {path}:{line}", path = path, line = line)?;
         context_marker
             .apply_to(format!("{:>width$}", "|", width = indent))
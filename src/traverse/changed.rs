@@ -0,0 +1,255 @@
+//! Restrict the checks to the lines changed since a git revision.
+//!
+//! Runs the local `git` to obtain a diff without context lines of the
+//! working tree against the revision, so only added or modified lines
+//! are considered.
+
+use crate::{Range, SuggestionSet};
+
+use anyhow::{anyhow, bail, Result};
+use indexmap::IndexMap;
+use log::debug;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Run `git` with the given arguments in `dir` and return its stdout.
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| anyhow!("Failed to run git in {}", dir.display()).context(e))?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse the new side of a hunk header `@@ -1,2 +3,4 @@` into a 1-indexed, exclusive line range.
+fn parse_hunk_header(line: &str) -> Option<Range> {
+    let new = line
+        .strip_prefix("@@ ")?
+        .split(' ')
+        .find(|part| part.starts_with('+'))?;
+    let mut iter = new[1..].splitn(2, ',');
+    let start = iter.next()?.parse::<usize>().ok()?;
+    let count = match iter.next() {
+        Some(count) => count.parse::<usize>().ok()?,
+        None => 1,
+    };
+    Some(start..(start + count))
+}
+
+/// Lines added or modified per file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedLines {
+    /// Absolute paths to the 1-indexed, exclusive line ranges.
+    per_file: IndexMap<PathBuf, Vec<Range>>,
+}
+
+impl ChangedLines {
+    /// Determine the lines changed in the working tree relative to `rev`.
+    pub fn since(rev: &str, cwd: &Path) -> Result<Self> {
+        let root = git(cwd, &["rev-parse", "--show-toplevel"])?;
+        let root = PathBuf::from(root.trim());
+        let root = root.canonicalize().map_err(|e| {
+            anyhow!("Failed to canonicalize git root {}", root.display()).context(e)
+        })?;
+        let diff = git(
+            &root,
+            &[
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--unified=0",
+                // neither `diff.noprefix` nor `diff.mnemonicPrefix` may change the paths
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
+                "--no-color",
+                "--no-ext-diff",
+                rev,
+                "--",
+            ],
+        )?;
+        let changed = Self::parse(&diff, &root);
+        debug!(
+            "Found changes in {} files since {}",
+            changed.per_file.len(),
+            rev
+        );
+        Ok(changed)
+    }
+
+    /// Parse a unified diff, the paths within are relative to `root`.
+    ///
+    /// Paths are expected with the default `b/` prefix, but are accepted without as well.
+    pub fn parse(diff: &str, root: &Path) -> Self {
+        let mut per_file = IndexMap::<PathBuf, Vec<Range>>::new();
+        let mut current: Option<PathBuf> = None;
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                // deleted files have no new side
+                current = match path {
+                    "/dev/null" => None,
+                    path => Some(root.join(path.strip_prefix("b/").unwrap_or(path))),
+                };
+            } else if line.starts_with("@@ ") {
+                if let (Some(path), Some(range)) = (current.as_ref(), parse_hunk_header(line)) {
+                    if !range.is_empty() {
+                        per_file.entry(path.clone()).or_default().push(range);
+                    }
+                }
+            }
+        }
+        Self { per_file }
+    }
+
+    /// The changed files which can be checked, limited to the given `within` paths if any.
+    pub fn files(&self, within: &[PathBuf], cwd: &Path) -> Vec<PathBuf> {
+        let within = within
+            .iter()
+            .filter_map(|path| cwd.join(path).canonicalize().ok())
+            .collect::<Vec<_>>();
+        self.per_file
+            .keys()
+            .filter(|path| {
+                path.extension()
+                    .map(|ext| ext == "rs" || ext == "md")
+                    .unwrap_or(false)
+            })
+            .filter(|path| path.is_file())
+            .filter(|path| within.is_empty() || within.iter().any(|dir| path.starts_with(dir)))
+            .cloned()
+            .collect()
+    }
+
+    /// Retain only the suggestions which intersect with a changed line.
+    pub fn filter<'s>(&self, set: SuggestionSet<'s>) -> SuggestionSet<'s> {
+        let mut filtered = SuggestionSet::new();
+        for (origin, suggestions) in set {
+            let lines = match self.per_file.get(origin.as_path()) {
+                Some(lines) => lines,
+                None => continue,
+            };
+            let touched = suggestions
+                .into_iter()
+                .filter(|suggestion| {
+                    lines.iter().any(|lines| {
+                        lines.start <= suggestion.span.end.line
+                            && suggestion.span.start.line < lines.end
+                    })
+                })
+                .collect::<Vec<_>>();
+            if !touched.is_empty() {
+                filtered.extend(origin, touched);
+            }
+        }
+        filtered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::documentation::ContentOrigin;
+    use crate::output::tests::{chunk, suggestion};
+
+    const DIFF: &str = r#"diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1 +1 @@
+-/// Old
++/// New
+@@ -10,0 +11,3 @@ fn x() {
++/// Added
++/// lines
++fn y() {}
+@@ -20,2 +23,0 @@
+-/// Removed
+-fn z() {}
+diff --git a/README.md b/README.md
+deleted file mode 100644
+--- a/README.md
++++ /dev/null
+@@ -1,2 +0,0 @@
+-# Title
+-Text
+"#;
+
+    #[test]
+    fn hunks() {
+        assert_eq!(parse_hunk_header("@@ -1 +1 @@"), Some(1..2));
+        assert_eq!(
+            parse_hunk_header("@@ -10,0 +11,3 @@ fn x() {"),
+            Some(11..14)
+        );
+        assert_eq!(parse_hunk_header("@@ -20,2 +23,0 @@"), Some(23..23));
+        assert_eq!(parse_hunk_header("diff --git"), None);
+    }
+
+    #[test]
+    fn parse_diff() {
+        let root = Path::new("/repo");
+        let changed = ChangedLines::parse(DIFF, root);
+        assert_eq!(changed.per_file.len(), 1);
+        assert_eq!(
+            changed.per_file.get(&root.join("src/lib.rs")),
+            Some(&vec![1..2, 11..14])
+        );
+    }
+
+    #[test]
+    fn parse_diff_without_prefix() {
+        const NOPREFIX: &str = r#"diff --git src/lib.rs src/lib.rs
+index 1111111..2222222 100644
+--- src/lib.rs
++++ src/lib.rs
+@@ -1 +1 @@
+-/// Old
++/// New
+diff --git README.md README.md
+deleted file mode 100644
+--- README.md
++++ /dev/null
+@@ -1,2 +0,0 @@
+-# Title
+-Text
+"#;
+        let root = Path::new("/repo");
+        let changed = ChangedLines::parse(NOPREFIX, root);
+        assert_eq!(changed.per_file.len(), 1);
+        assert_eq!(
+            changed.per_file.get(&root.join("src/lib.rs")),
+            Some(&vec![Range { start: 1, end: 2 }])
+        );
+    }
+
+    #[test]
+    fn filter_suggestions() {
+        let chunk = chunk();
+        let mut set = SuggestionSet::new();
+        let mut untouched = suggestion(&chunk);
+        untouched.origin = ContentOrigin::RustSourceFile(PathBuf::from("/repo/src/lib.rs"));
+        let mut touched = untouched.clone();
+        touched.span.start.line = 12;
+        touched.span.end.line = 12;
+        untouched.span.start.line = 5;
+        untouched.span.end.line = 5;
+        set.add(untouched.origin.clone(), untouched);
+        set.add(touched.origin.clone(), touched);
+        let mut elsewhere = suggestion(&chunk);
+        elsewhere.origin = ContentOrigin::RustSourceFile(PathBuf::from("/repo/src/main.rs"));
+        set.add(elsewhere.origin.clone(), elsewhere);
+
+        let changed = ChangedLines::parse(DIFF, Path::new("/repo"));
+        let filtered = changed.filter(set);
+        assert_eq!(filtered.total_count(), 1);
+        let (_origin, suggestions) = filtered.iter().next().unwrap();
+        assert_eq!(suggestions[0].span.start.line, 12);
+    }
+}
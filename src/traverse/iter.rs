@@ -128,8 +128,12 @@ impl Iterator for TraverseModulesIter {
 
 /// traverse path with a depth limit, if the path is a directory all its children will be collected
 /// instead
-pub(crate) fn traverse(path: &Path) -> Result<impl Iterator<Item = Documentation>> {
-    traverse_with_depth_limit(path, usize::MAX)
+pub(crate) fn traverse(
+    path: &Path,
+    dev_comments: bool,
+    string_literals: bool,
+) -> Result<impl Iterator<Item = Documentation>> {
+    traverse_with_depth_limit(path, usize::MAX, dev_comments, string_literals)
 }
 
 /// traverse path with a depth limit, if the path is a directory all its children will be collected
@@ -137,11 +141,18 @@ pub(crate) fn traverse(path: &Path) -> Result<impl Iterator<Item = Documentation
 pub(crate) fn traverse_with_depth_limit(
     path: &Path,
     max_depth: usize,
+    dev_comments: bool,
+    string_literals: bool,
 ) -> Result<impl Iterator<Item = Documentation>> {
     let it = TraverseModulesIter::with_depth_limit(path, max_depth)?
-        .filter_map(|path: PathBuf| -> Option<Documentation> {
+        .filter_map(move |path: PathBuf| -> Option<Documentation> {
             fs::read_to_string(&path).ok().map(|content| {
-                Documentation::from((ContentOrigin::RustSourceFile(path), content.as_str()))
+                Documentation::load_rust(
+                    ContentOrigin::RustSourceFile(path),
+                    content.as_str(),
+                    dev_comments,
+                    string_literals,
+                )
             })
         })
         .filter(|documentation| !documentation.is_empty());
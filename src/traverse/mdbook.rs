@@ -0,0 +1,138 @@
+//! Chapters of an mdbook project.
+//!
+//! The chapters are the markdown files linked from `SUMMARY.md` within
+//! the source directory declared in `book.toml`.
+
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Deserialize)]
+struct BookToml {
+    #[serde(default)]
+    book: BookSection,
+}
+
+#[derive(Debug, Deserialize)]
+struct BookSection {
+    #[serde(default = "BookSection::default_src")]
+    src: PathBuf,
+}
+
+impl BookSection {
+    fn default_src() -> PathBuf {
+        PathBuf::from("src")
+    }
+}
+
+impl Default for BookSection {
+    fn default() -> Self {
+        Self {
+            src: Self::default_src(),
+        }
+    }
+}
+
+/// All chapters of the book described by `book_toml`, including `SUMMARY.md` itself.
+pub(crate) fn chapters(book_toml: &Path) -> Result<Vec<PathBuf>> {
+    let content = fs::read_to_string(book_toml)
+        .map_err(|e| anyhow!("Failed to read {}", book_toml.display()).context(e))?;
+    let book: BookToml = toml::from_str(&content)
+        .map_err(|e| anyhow!("Failed to parse {}", book_toml.display()).context(e))?;
+    let src = book_toml
+        .parent()
+        .expect("A file always has a parent directory. qed")
+        .join(&book.book.src);
+    let summary = src.join("SUMMARY.md");
+    let content = fs::read_to_string(&summary)
+        .map_err(|e| anyhow!("Failed to read {}", summary.display()).context(e))?;
+
+    let mut acc = vec![summary.clone()];
+    for event in Parser::new_ext(&content, Options::all()) {
+        let dest = match event {
+            Event::Start(Tag::Link(_link_type, dest, _title)) => dest,
+            _ => continue,
+        };
+        // the chapter itself, not the heading within
+        let dest = dest.split('#').next().unwrap_or_default();
+        // draft chapters have no file yet
+        if dest.is_empty() {
+            continue;
+        }
+        let chapter = src.join(dest);
+        if !chapter.is_file() {
+            warn!(
+                "Chapter {} listed in {} does not exist",
+                chapter.display(),
+                summary.display()
+            );
+            continue;
+        }
+        if !acc.contains(&chapter) {
+            acc.push(chapter);
+        }
+    }
+    debug!("Found {} chapters in {}", acc.len(), book_toml.display());
+    Ok(acc)
+}
+
+/// Blank out mdbook preprocessor directives such as `{{#include file.rs}}`.
+///
+/// The included content is checked where it originates, if at all. Every char
+/// is replaced by a space, so the remaining content keeps its spans.
+pub(crate) fn mask_directives(content: &str) -> String {
+    let mut masked = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{#") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end + 2,
+            None => break,
+        };
+        masked.push_str(&rest[..start]);
+        masked.extend(
+            rest[start..end]
+                .chars()
+                .map(|c| if c == '\n' { '\n' } else { ' ' }),
+        );
+        rest = &rest[end..];
+    }
+    masked.push_str(rest);
+    masked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary() {
+        let book = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("demo/book");
+        assert_eq!(
+            chapters(&book.join("book.toml")).unwrap(),
+            vec![
+                book.join("src/SUMMARY.md"),
+                book.join("src/intro.md"),
+                book.join("src/chapter/usage.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn directives() {
+        const CHAPTER: &str = "# Title\n\n{{#include ../README.md}}\nTäxt {{#title Ünicode}} end";
+        let masked = mask_directives(CHAPTER);
+        assert_eq!(masked.chars().count(), CHAPTER.chars().count());
+        assert_eq!(
+            masked,
+            format!(
+                "# Title\n\n{}\nTäxt {} end",
+                " ".repeat("{{#include ../README.md}}".len()),
+                " ".repeat("{{#title Ünicode}}".chars().count())
+            )
+        );
+        assert_eq!(mask_directives("{{#unterminated"), "{{#unterminated");
+    }
+}
@@ -7,14 +7,61 @@ use crate::Documentation;
 
 use anyhow::{anyhow, bail, Error, Result};
 use log::{debug, trace, warn};
-use std::convert::TryFrom;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-pub(crate) fn cwd() -> Result<PathBuf> {
+pub fn cwd() -> Result<PathBuf> {
     std::env::current_dir().map_err(|_e| anyhow::anyhow!("Missing cwd!"))
 }
 
+/// The root of the project `dir` belongs to.
+///
+/// That is the closest workspace root, otherwise the directory of the closest
+/// manifest, or `dir` itself if there is none.
+pub fn project_root(dir: &Path) -> PathBuf {
+    let mut manifest_dirs = dir
+        .ancestors()
+        .filter(|dir| dir.join("Cargo.toml").is_file());
+    let closest = match manifest_dirs.next() {
+        Some(closest) => closest,
+        None => return dir.to_owned(),
+    };
+    let is_workspace = |dir: &Path| {
+        fs::read_to_string(dir.join("Cargo.toml"))
+            .ok()
+            .and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+            .map(|manifest| manifest.get("workspace").is_some())
+            .unwrap_or(false)
+    };
+    std::iter::once(closest)
+        .chain(manifest_dirs)
+        .find(|dir| is_workspace(dir))
+        .unwrap_or(closest)
+        .to_owned()
+}
+
+/// All source files of the crate the source file `path` belongs to.
+///
+/// Follows the `mod` declarations of the crate roots of the closest manifest directory,
+/// returns nothing if `path` is not part of a crate.
+pub(crate) fn crate_sources(path: &Path) -> Vec<PathBuf> {
+    let manifest_dir = match path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Cargo.toml").is_file())
+    {
+        Some(manifest_dir) => manifest_dir,
+        None => return Vec::new(),
+    };
+    ["src/lib.rs", "src/main.rs"]
+        .iter()
+        .map(|root| manifest_dir.join(root))
+        .filter(|root| root.is_file())
+        .filter_map(|root| TraverseModulesIter::new(root).ok())
+        .flatten()
+        .collect()
+}
+
 #[cfg(test)]
 fn manifest_dir() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -22,7 +69,10 @@ fn manifest_dir() -> PathBuf {
 
 use std::collections::VecDeque;
 
+mod changed;
 mod iter;
+mod mdbook;
+pub use changed::ChangedLines;
 pub use iter::*;
 
 use proc_macro2::Spacing;
@@ -138,8 +188,11 @@ pub(crate) fn extract_modules_from_file<P: AsRef<Path>>(path: P) -> Result<Vec<P
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum CheckEntity {
     Markdown(PathBuf),
+    /// A chapter of an mdbook, which may contain preprocessor directives.
+    BookChapter(PathBuf),
     Source(PathBuf, bool), // recurse is the bool
-    ManifestDescription(String),
+    /// The package metadata of the given `Cargo.toml`.
+    ManifestMetadata(PathBuf),
 }
 
 fn load_manifest<P: AsRef<Path>>(manifest_dir: P) -> Result<cargo_toml::Manifest> {
@@ -221,8 +274,13 @@ fn extract_readme(
                 );
             }
         }
-        if let Some(description) = package.description {
-            acc.push(CheckEntity::ManifestDescription(description.to_owned()));
+        if package.description.is_some()
+            || !package.keywords.is_empty()
+            || !package.categories.is_empty()
+        {
+            acc.push(CheckEntity::ManifestMetadata(
+                manifest_dir.join("Cargo.toml"),
+            ));
         }
     }
     Ok(acc)
@@ -294,11 +352,11 @@ fn handle_manifest<P: AsRef<Path>>(manifest_dir: P, skip_readme: bool) -> Result
 }
 
 /// Extract all chunks from
-pub(crate) fn extract(
+pub fn extract(
     mut paths: Vec<PathBuf>,
     mut recurse: bool,
     skip_readme: bool,
-    _config: &Config,
+    config: &Config,
 ) -> Result<Documentation> {
     let cwd = cwd()?;
     // if there are no arguments, pretend to be told to check the whole project
@@ -315,6 +373,7 @@ pub(crate) fn extract(
         Missing(PathBuf),
         Source(PathBuf),
         Markdown(PathBuf),
+        Book(PathBuf),
     }
 
     // stage 1 - obtain canonical paths
@@ -338,6 +397,7 @@ pub(crate) fn extract(
             if meta.is_file() {
                 match path.file_name().map(|x| x.to_str()).flatten() {
                     Some(file_name) if file_name == "Cargo.toml" => Extraction::Manifest(path),
+                    Some(file_name) if file_name == "book.toml" => Extraction::Book(path),
                     Some(file_name) if file_name.ends_with(".md") => Extraction::Markdown(path),
                     Some(file_name) if file_name.ends_with(".rs") => Extraction::Source(path),
                     _ => {
@@ -347,16 +407,24 @@ pub(crate) fn extract(
                 }
             } else if meta.is_dir() {
                 let cargo_toml = to_manifest_dir(&path).unwrap().join("Cargo.toml");
-                if cargo_toml.is_file() {
-                    Extraction::Manifest(cargo_toml)
-                } else {
-                    // @todo should we just collect all .rs files here instead?
-
-                    // we know it's a directory, and we limit the entries to 0 levels,
-                    // will cause to yield all "^.*\.rs$" files in that dir
-                    // which is what we want in this case
-                    flow.extend(TraverseModulesIter::with_depth_limit(&path, 0)?);
-                    continue;
+                let book_toml = path.join("book.toml");
+                match (cargo_toml.is_file(), book_toml.is_file()) {
+                    (true, true) => {
+                        // a crate with its book alongside
+                        files_to_check.push(Extraction::Manifest(cargo_toml));
+                        Extraction::Book(book_toml)
+                    }
+                    (true, false) => Extraction::Manifest(cargo_toml),
+                    (false, true) => Extraction::Book(book_toml),
+                    (false, false) => {
+                        // @todo should we just collect all .rs files here instead?
+
+                        // we know it's a directory, and we limit the entries to 0 levels,
+                        // will cause to yield all "^.*\.rs$" files in that dir
+                        // which is what we want in this case
+                        flow.extend(TraverseModulesIter::with_depth_limit(&path, 0)?);
+                        continue;
+                    }
                 }
             } else {
                 Extraction::Missing(path)
@@ -384,6 +452,10 @@ pub(crate) fn extract(
                 ),
                 Extraction::Source(path) => acc.push(CheckEntity::Source(path, recurse)),
                 Extraction::Markdown(path) => acc.push(CheckEntity::Markdown(path)),
+                Extraction::Book(ref book_toml) => {
+                    let chapters = mdbook::chapters(book_toml)?;
+                    acc.extend(chapters.into_iter().map(CheckEntity::BookChapter));
+                }
             }
             Ok(acc)
         })?;
@@ -394,59 +466,56 @@ pub(crate) fn extract(
         .try_fold::<Documentation, _, Result<Documentation>>(
             Documentation::new(),
             |mut docs, item| {
+                let book_chapter = matches!(item, CheckEntity::BookChapter(_));
                 match item {
                     CheckEntity::Source(path, recurse) => {
                         if recurse {
-                            let iter = traverse(path.as_path())?;
+                            let iter = traverse(
+                                path.as_path(),
+                                config.dev_comments,
+                                config.string_literals,
+                            )?;
                             docs.extend(iter);
                         } else {
                             let content: String = fs::read_to_string(&path).map_err(|e| {
                                 anyhow!("Failed to read {}", path.display()).context(e)
                             })?;
-                            if let Ok(cluster) = Clusters::try_from(content.as_str()) {
-                                let chunks = Vec::<CheckableChunk>::from(cluster);
-                                docs.add(ContentOrigin::RustSourceFile(path.to_owned()), chunks);
-                            } else {
-                                log::error!("BUG: Failed to create cluster for {}", path.display());
-                            }
+                            docs.join(Documentation::load_rust(
+                                ContentOrigin::RustSourceFile(path.to_owned()),
+                                content.as_str(),
+                                config.dev_comments,
+                                config.string_literals,
+                            ));
                         }
                     }
-                    CheckEntity::Markdown(path) => {
-                        let content = std::fs::read_to_string(&path).map_err(|e| {
+                    CheckEntity::Markdown(path) | CheckEntity::BookChapter(path) => {
+                        let mut content = std::fs::read_to_string(&path).map_err(|e| {
                             anyhow!("Common mark / markdown file does not exist").context(e)
                         })?;
+                        if book_chapter {
+                            content = mdbook::mask_directives(&content);
+                        }
                         if content.len() < 1 {
                             bail!("Common mark / markdown file is empty")
                         }
-                        // extract the full content span and range
-                        let start = LineColumn { line: 1, column: 0 };
-                        let end = content
-                            .lines()
-                            .enumerate()
-                            .last()
-                            .map(|(idx, line)| (idx + 1, line))
-                            .map(|(lineno, line)| LineColumn {
-                                line: lineno,
-                                column: line.chars().count(),
-                            })
-                            .ok_or_else(|| {
-                                anyhow!(
-                                    "Common mark / markdown file does not contain a single line"
-                                )
-                            })?;
-
-                        let span = Span { start, end };
-                        let source_mapping = indexmap::indexmap! {
-                           0..content.chars().count() => span
-                        };
-                        docs.add(
+                        docs.join(Documentation::load_commonmark(
                             ContentOrigin::CommonMarkFile(path.to_owned()),
-                            vec![CheckableChunk::from_string(content, source_mapping)],
-                        );
+                            &content,
+                            config.dev_comments,
+                            config.string_literals,
+                        ));
                     }
-                    other => {
-                        warn!("Did not impl handling of {:?} type files", other);
-                        // @todo generate Documentation structs from non-file sources
+                    CheckEntity::ManifestMetadata(path) => {
+                        let content = fs::read_to_string(&path)
+                            .map_err(|e| anyhow!("Failed to read {}", path.display()).context(e))?;
+                        let manifest = Documentation::load_manifest(
+                            ContentOrigin::CargoManifest(path.clone()),
+                            &content,
+                        )
+                        .map_err(|e| {
+                            anyhow!("Failed to parse manifest {}", path.display()).context(e)
+                        })?;
+                        docs.join(manifest);
                     }
                 }
                 Ok(docs)
@@ -494,9 +563,7 @@ mod tests {
             extract_readme(&manifest, &dir).expect("Must succeed"),
             vec![
                 CheckEntity::Markdown(demo_dir().join("README.md")),
-                CheckEntity::ManifestDescription(
-                    "A silly demo with plenty of spelling mistakes for cargo-spellcheck demos and CI".to_string()
-                ),
+                CheckEntity::ManifestMetadata(demo_dir().join("Cargo.toml")),
             ]
         );
     }
@@ -512,6 +579,68 @@ mod tests {
         )
     }
 
+    #[test]
+    fn project_roots() {
+        assert_eq!(project_root(&demo_dir().join("src/nested")), demo_dir());
+        assert_eq!(project_root(&manifest_dir().join("src")), manifest_dir());
+
+        let dir =
+            std::env::temp_dir().join(format!("cargo-spellcheck-root-{}", std::process::id()));
+        fs::create_dir_all(dir.join("member/src")).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"member\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("member/Cargo.toml"),
+            "[package]\nname = \"member\"\n",
+        )
+        .unwrap();
+        assert_eq!(project_root(&dir.join("member/src")), dir);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn book_next_to_manifest() {
+        let dir =
+            std::env::temp_dir().join(format!("cargo-spellcheck-book-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(dir.join("guide")).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"booked\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("src/lib.rs"), "//! Crate docs.\n").unwrap();
+        fs::write(dir.join("book.toml"), "[book]\nsrc = \"guide\"\n").unwrap();
+        fs::write(
+            dir.join("guide/SUMMARY.md"),
+            "# Summary\n\n[Intro](intro.md)\n",
+        )
+        .unwrap();
+        fs::write(dir.join("guide/intro.md"), "# Intro\n").unwrap();
+
+        let docs = extract(vec![dir.clone()], false, true, &Config::default()).unwrap();
+        let files = into_hashset(docs.into_iter().map(|(origin, _chunks)| {
+            origin
+                .as_path()
+                .strip_prefix(&dir)
+                .expect("Must have common prefix")
+                .to_owned()
+        }));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            files,
+            into_hashset(
+                ["src/lib.rs", "guide/SUMMARY.md", "guide/intro.md"]
+                    .iter()
+                    .map(PathBuf::from)
+            )
+        );
+    }
+
     use std::collections::HashSet;
     use std::hash::Hash;
 
@@ -582,6 +711,7 @@ mod tests {
     #[test]
     fn traverse_manifest_1() {
         extract_test!(["Cargo.toml"] + false => [
+            "Cargo.toml",
             "README.md",
             "src/lib.rs",
             "src/main.rs",
@@ -612,6 +742,7 @@ mod tests {
     ]);
 
     extract_test!(traverse_manifest_dir_rec, ["."] + true => [
+        "Cargo.toml",
         "README.md",
         "src/lib.rs",
         "src/main.rs",
@@ -625,6 +756,7 @@ mod tests {
     ]);
 
     extract_test!(traverse_manifest_rec, ["Cargo.toml"] + true => [
+        "Cargo.toml",
         "README.md",
         "src/lib.rs",
         "src/main.rs",
@@ -637,6 +769,12 @@ mod tests {
         "src/nested/mod.rs",
     ]);
 
+    extract_test!(traverse_book, ["book"] + false => [
+        "book/src/SUMMARY.md",
+        "book/src/intro.md",
+        "book/src/chapter/usage.md",
+    ]);
+
     extract_test!(traverse_nested_mod_rs_1, ["src/nested/mod.rs"] + false => [
         "src/nested/mod.rs"
     ]);
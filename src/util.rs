@@ -86,6 +86,16 @@ pub fn sub_chars(s: &str, range: Range) -> String {
         .collect::<String>()
 }
 
+/// 64 bit FNV-1a hash, stable across platforms and compiler versions
+/// unlike `std::collections::hash_map::DefaultHasher`.
+pub fn fnv1a64(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;